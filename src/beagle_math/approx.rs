@@ -0,0 +1,250 @@
+use crate::beagle_math::{Aabb, Color, DualQuaternion, Frustum, Matrix3, Matrix4, Plane, Quat, Ray, Real, Sphere, Transform, Vec2, Vec3, Vec4};
+
+// Floating point math rarely gives exactly the value you would get on paper, so comparing results with == is almost never
+// what you want. ApproxEq offers two kinds of comparison:
+// - approx_eq_eps: the difference must be at most epsilon, scaled up by the size of the values once they are larger than 1.0.
+//   This makes epsilon an absolute tolerance close to zero and a relative tolerance for large values, such as the elements of a projection matrix.
+// - approx_eq_ulps: the values must be at most "max_ulps" representable floating point numbers apart.
+//   This adapts to the magnitude of the values by itself, but is useless close to zero, where the representable numbers are extremely dense.
+// Types made of several scalars are approximately equal when every scalar is.
+pub trait ApproxEq {
+    type Epsilon: Copy;
+
+    // The epsilon used by approx_eq, picked to absorb the rounding errors of a handful of operations.
+    fn default_epsilon() -> Self::Epsilon;
+
+    fn approx_eq_eps(&self, other: &Self, epsilon: Self::Epsilon) -> bool;
+
+    fn approx_eq_ulps(&self, other: &Self, max_ulps: u32) -> bool;
+
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.approx_eq_eps(other, Self::default_epsilon())
+    }
+}
+
+// NaN is never approximately equal to anything, just like it is never equal to anything.
+macro_rules! impl_approx_eq_for_float {
+    ($float:ident, $bits:ident, $default_epsilon:expr) => {
+        impl ApproxEq for $float {
+            type Epsilon = $float;
+
+            fn default_epsilon() -> $float {
+                $default_epsilon
+            }
+
+            fn approx_eq_eps(&self, other: &$float, epsilon: $float) -> bool {
+                if self == other {
+                    return true;
+                }
+
+                (self - other).abs() <= epsilon * self.abs().max(other.abs()).max(1.0)
+            }
+
+            fn approx_eq_ulps(&self, other: &$float, max_ulps: u32) -> bool {
+                if self == other {
+                    return true;
+                }
+
+                // Floating point numbers of the same sign are ordered the same way as their bits read as integers,
+                // so the difference of the bits is the number of representable values between them.
+                if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+                    return false;
+                }
+
+                let distance = (self.to_bits() as $bits - other.to_bits() as $bits).abs();
+
+                distance <= max_ulps as $bits
+            }
+        }
+    };
+}
+
+impl_approx_eq_for_float!(f32, i64, 1e-5);
+impl_approx_eq_for_float!(f64, i128, 1e-12);
+
+macro_rules! impl_approx_eq_for_components {
+    ($type:ident { $($field:ident),+ }) => {
+        impl<T: Real> ApproxEq for $type<T> {
+            type Epsilon = T;
+
+            fn default_epsilon() -> T {
+                T::default_epsilon()
+            }
+
+            fn approx_eq_eps(&self, other: &$type<T>, epsilon: T) -> bool {
+                $(self.$field.approx_eq_eps(&other.$field, epsilon))&&+
+            }
+
+            fn approx_eq_ulps(&self, other: &$type<T>, max_ulps: u32) -> bool {
+                $(self.$field.approx_eq_ulps(&other.$field, max_ulps))&&+
+            }
+        }
+    };
+}
+
+impl_approx_eq_for_components!(Vec2 { x, y });
+impl_approx_eq_for_components!(Vec3 { x, y, z });
+impl_approx_eq_for_components!(Vec4 { x, y, z, w });
+
+// Note that q and -q represent the same rotation, but are not approximately equal as quaternions.
+impl_approx_eq_for_components!(Quat { x, y, z, w });
+
+macro_rules! impl_approx_eq_for_matrix {
+    ($matrix:ident) => {
+        impl<T: Real> ApproxEq for $matrix<T> {
+            type Epsilon = T;
+
+            fn default_epsilon() -> T {
+                T::default_epsilon()
+            }
+
+            fn approx_eq_eps(&self, other: &$matrix<T>, epsilon: T) -> bool {
+                self.matrix.iter().zip(other.matrix.iter()).all(|(a, b)| a.approx_eq_eps(b, epsilon))
+            }
+
+            fn approx_eq_ulps(&self, other: &$matrix<T>, max_ulps: u32) -> bool {
+                self.matrix.iter().zip(other.matrix.iter()).all(|(a, b)| a.approx_eq_ulps(b, max_ulps))
+            }
+        }
+    };
+}
+
+impl_approx_eq_for_matrix!(Matrix3);
+impl_approx_eq_for_matrix!(Matrix4);
+
+impl ApproxEq for Transform {
+    type Epsilon = f32;
+
+    fn default_epsilon() -> f32 {
+        f32::default_epsilon()
+    }
+
+    fn approx_eq_eps(&self, other: &Transform, epsilon: f32) -> bool {
+        self.translation.approx_eq_eps(&other.translation, epsilon)
+            && self.rotation.approx_eq_eps(&other.rotation, epsilon)
+            && self.scale.approx_eq_eps(&other.scale, epsilon)
+    }
+
+    fn approx_eq_ulps(&self, other: &Transform, max_ulps: u32) -> bool {
+        self.translation.approx_eq_ulps(&other.translation, max_ulps)
+            && self.rotation.approx_eq_ulps(&other.rotation, max_ulps)
+            && self.scale.approx_eq_ulps(&other.scale, max_ulps)
+    }
+}
+
+// The geometry types are made of vectors and scalars, and are approximately equal when all of those are.
+macro_rules! impl_approx_eq_for_fields {
+    ($type:ident { $($field:ident),+ }) => {
+        impl ApproxEq for $type {
+            type Epsilon = f32;
+
+            fn default_epsilon() -> f32 {
+                f32::default_epsilon()
+            }
+
+            fn approx_eq_eps(&self, other: &$type, epsilon: f32) -> bool {
+                $(self.$field.approx_eq_eps(&other.$field, epsilon))&&+
+            }
+
+            fn approx_eq_ulps(&self, other: &$type, max_ulps: u32) -> bool {
+                $(self.$field.approx_eq_ulps(&other.$field, max_ulps))&&+
+            }
+        }
+    };
+}
+
+impl_approx_eq_for_fields!(Plane { normal, distance });
+impl_approx_eq_for_fields!(Ray { origin, direction });
+impl_approx_eq_for_fields!(Aabb { min, max });
+impl_approx_eq_for_fields!(Sphere { center, radius });
+impl_approx_eq_for_fields!(Frustum { left, right, bottom, top, near, far });
+
+impl ApproxEq for DualQuaternion {
+    type Epsilon = f32;
+
+    fn default_epsilon() -> f32 {
+        f32::default_epsilon()
+    }
+
+    fn approx_eq_eps(&self, other: &DualQuaternion, epsilon: f32) -> bool {
+        self.real.approx_eq_eps(&other.real, epsilon) && self.dual.approx_eq_eps(&other.dual, epsilon)
+    }
+
+    fn approx_eq_ulps(&self, other: &DualQuaternion, max_ulps: u32) -> bool {
+        self.real.approx_eq_ulps(&other.real, max_ulps) && self.dual.approx_eq_ulps(&other.dual, max_ulps)
+    }
+}
+
+impl ApproxEq for Color {
+    type Epsilon = f32;
+
+    fn default_epsilon() -> f32 {
+        f32::default_epsilon()
+    }
+
+    fn approx_eq_eps(&self, other: &Color, epsilon: f32) -> bool {
+        self.as_array().iter().zip(other.as_array().iter()).all(|(a, b)| a.approx_eq_eps(b, epsilon))
+    }
+
+    fn approx_eq_ulps(&self, other: &Color, max_ulps: u32) -> bool {
+        self.as_array().iter().zip(other.as_array().iter()).all(|(a, b)| a.approx_eq_ulps(b, max_ulps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    #[test]
+    fn should_scale_epsilon_with_magnitude_when_comparing_with_epsilon() {
+        // Assert
+        assert!(0.0f32.approx_eq_eps(&0.00001, 1e-4));
+        assert!(!0.0f32.approx_eq_eps(&0.001, 1e-4));
+        assert!(10000.0f32.approx_eq_eps(&10000.5, 1e-4));
+        assert!(!10000.0f32.approx_eq_eps(&10002.0, 1e-4));
+        assert!(!f32::NAN.approx_eq(&f32::NAN));
+    }
+
+    #[test]
+    fn should_count_representable_values_when_comparing_with_ulps() {
+        // Arrange
+        let one = 1.0f32;
+        let next = f32::from_bits(one.to_bits() + 1);
+        let after_next = f32::from_bits(one.to_bits() + 2);
+
+        // Assert
+        assert!(one.approx_eq_ulps(&next, 1));
+        assert!(!one.approx_eq_ulps(&after_next, 1));
+        assert!(!1.0f32.approx_eq_ulps(&-1.0, u32::MAX));
+        assert!(0.1f64.approx_eq_ulps(&(0.3 - 0.2), 4));
+    }
+
+    #[test]
+    fn should_compare_every_component_when_comparing_compound_types() {
+        // Arrange
+        let vector = Vector3::new(1.0, 2.0, 3.0);
+        let matrix = Mat4::rotate_y(0.5);
+
+        // Assert
+        assert!(vector.approx_eq(&Vector3::new(1.0, 2.0, 3.000001)));
+        assert!(!vector.approx_eq(&Vector3::new(1.0, 2.1, 3.0)));
+        assert!(matrix.mul(&matrix.inverse().unwrap()).approx_eq(&Mat4::identity()));
+        assert!(!matrix.approx_eq(&Mat4::identity()));
+        assert!(Transform::identity().approx_eq_ulps(&Transform::default(), 0));
+    }
+
+    #[test]
+    fn should_compare_every_field_when_comparing_geometry_types() {
+        // Arrange
+        let plane = Plane::from_point_normal(&Vector3::new(0.0, 2.0, 0.0), &Vector3::new(0.0, 3.0, 0.0));
+        let sphere = Sphere::new(Vector3::new(1.0, 2.0, 3.0), 4.0);
+
+        // Assert
+        assert!(plane.approx_eq(&Plane::new(Vector3::new(0.0, 1.0, 0.0), -2.000001)));
+        assert!(!plane.approx_eq(&Plane::new(Vector3::new(0.0, 1.0, 0.0), 2.0)));
+        assert!(sphere.approx_eq(&Sphere::new(Vector3::new(1.0, 2.0, 3.0), 4.00001)));
+        assert!(!sphere.approx_eq(&Sphere::new(Vector3::new(1.0, 2.1, 3.0), 4.0)));
+        assert!(Ray::new(Vector3::default(), Vector3::new(0.0, 0.0, 2.0)).approx_eq(&Ray::new(Vector3::default(), Vector3::new(0.0, 0.0, 1.0))));
+        assert!(!Aabb::new(Vector3::default(), Vector3::new(1.0, 1.0, 1.0)).approx_eq(&Aabb::new(Vector3::default(), Vector3::new(1.0, 1.0, 1.1))));
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt;
+
+use crate::beagle_math::{math, Lerp, Vector4};
+
+// How a color is combined with the color already in the render target, like the blend state of the output merger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    // Regular transparency, for colors with straight alpha.
+    Alpha,
+    // Transparency for colors whose red, green and blue are already multiplied by their alpha.
+    // This is what textures should be blended with, as filtering premultiplied texels does not bleed the color of invisible texels.
+    Premultiplied,
+    // Adds the color on top, for glowing things like the engine flame and explosions.
+    Additive,
+    // Darkens, for shadows and tinting.
+    Multiply,
+    // The opposite of multiply, which brightens.
+    Screen
+}
+
+// A color in LINEAR light, where doubling a value doubles the amount of light, so blending and lighting give the right result.
+// Colors picked in a paint program, hex codes and the bytes of a PNG are all sRGB encoded, and have to be converted
+// with the "srgb" constructors, or the picture ends up looking washed out.
+// The alpha is "straight" (not premultiplied), unless premultiply has been called.
+// The layout matches a float4 (DXGI_FORMAT_R32G32B32A32_FLOAT), so it can be placed directly in a vertex.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32
+}
+
+impl Color {
+    pub const TRANSPARENT: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+    pub const BLACK: Color = Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 };
+    pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 };
+
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color {
+            r,
+            g,
+            b,
+            a
+        }
+    }
+
+    // Alpha is never sRGB encoded, so it is used as it is.
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Color {
+        Color::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    pub fn to_srgb(&self) -> [f32; 4] {
+        [linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b), self.a]
+    }
+
+    pub fn from_srgb8(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color::from_srgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    // Values outside [0, 1], such as HDR colors, are clamped.
+    pub fn to_srgb8(&self) -> [u8; 4] {
+        let [r, g, b, a] = self.to_srgb();
+        let to_byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        [to_byte(r), to_byte(g), to_byte(b), to_byte(a)]
+    }
+
+    // Parses "#RRGGBB" or "#RRGGBBAA" (the # is optional), as used by paint programs and on the web.
+    // Returns None if the text is not six or eight hexadecimal digits.
+    pub fn from_hex(text: &str) -> Option<Color> {
+        let digits = text.strip_prefix('#').unwrap_or(text);
+
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let value = u32::from_str_radix(digits, 16).ok()?;
+
+        match digits.len() {
+            6 => Some(Color::from_srgb8((value >> 16) as u8, (value >> 8) as u8, value as u8, 255)),
+            8 => Some(Color::from_srgb8((value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8)),
+            _ => None
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        let [r, g, b, a] = self.to_srgb8();
+
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+
+    // The packed u32 formats store the sRGB encoded bytes, with the first channel of the name in the lowest byte.
+    // This matches the order of the bytes in memory on a little endian CPU, and so the layout of DXGI_FORMAT_R8G8B8A8_UNORM_SRGB.
+    pub fn pack_rgba8(&self) -> u32 {
+        u32::from_le_bytes(self.to_srgb8())
+    }
+
+    pub fn unpack_rgba8(packed: u32) -> Color {
+        let [r, g, b, a] = packed.to_le_bytes();
+        Color::from_srgb8(r, g, b, a)
+    }
+
+    // The layout of DXGI_FORMAT_B8G8R8A8_UNORM_SRGB, which is the preferred back buffer format of many GPUs.
+    pub fn pack_bgra8(&self) -> u32 {
+        let [r, g, b, a] = self.to_srgb8();
+        u32::from_le_bytes([b, g, r, a])
+    }
+
+    pub fn unpack_bgra8(packed: u32) -> Color {
+        let [b, g, r, a] = packed.to_le_bytes();
+        Color::from_srgb8(r, g, b, a)
+    }
+
+    // The hue is in degrees, and saturation and value in [0, 1].
+    // Like a color picker, these work on the sRGB encoded color, so the steps in brightness look even.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+        let chroma = value * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = value - chroma;
+
+        Color::from_srgb(r + m, g + m, b + m, alpha)
+    }
+
+    // Returns the hue, saturation and value. Grays have a hue of 0.0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_srgb();
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        (rgb_to_hue(r, g, b, max, chroma), saturation, max)
+    }
+
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = lightness - chroma / 2.0;
+
+        Color::from_srgb(r + m, g + m, b + m, alpha)
+    }
+
+    // Returns the hue, saturation and lightness. Grays have a hue of 0.0.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let [r, g, b, _] = self.to_srgb();
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (max + min) / 2.0;
+        let saturation = if chroma > 0.0 { chroma / (1.0 - (2.0 * lightness - 1.0).abs()) } else { 0.0 };
+
+        (rgb_to_hue(r, g, b, max, chroma), saturation, lightness)
+    }
+
+    pub fn premultiply(&self) -> Color {
+        Color::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    // A fully transparent color has lost its red, green and blue, and becomes transparent black.
+    pub fn unpremultiply(&self) -> Color {
+        if self.a == 0.0 {
+            return Color::TRANSPARENT;
+        }
+
+        Color::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    // Blends this color on top of "destination". For BlendMode::Premultiplied, both colors must be premultiplied,
+    // and so is the result. For the other modes, both colors have straight alpha.
+    pub fn blend(&self, destination: &Color, mode: BlendMode) -> Color {
+        let source = *self;
+        let alpha = source.a + destination.a * (1.0 - source.a);
+
+        // Mixes the result of the blend function with the destination, by how opaque the source is.
+        let mix = |blended: fn(f32, f32) -> f32| {
+            let channel = |s: f32, d: f32| d + (blended(s, d) - d) * source.a;
+            Color::new(channel(source.r, destination.r), channel(source.g, destination.g), channel(source.b, destination.b), alpha)
+        };
+
+        match mode {
+            BlendMode::Alpha => mix(|s, _| s),
+            BlendMode::Premultiplied => Color::new(
+                source.r + destination.r * (1.0 - source.a),
+                source.g + destination.g * (1.0 - source.a),
+                source.b + destination.b * (1.0 - source.a),
+                alpha
+            ),
+            BlendMode::Additive => Color::new(
+                destination.r + source.r * source.a,
+                destination.g + source.g * source.a,
+                destination.b + source.b * source.a,
+                destination.a
+            ),
+            BlendMode::Multiply => mix(|s, d| s * d),
+            BlendMode::Screen => mix(|s, d| 1.0 - (1.0 - s) * (1.0 - d))
+        }
+    }
+
+    pub fn as_array(&self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+}
+
+// Interpolates in linear light, which avoids the dark band in the middle of a fade between two bright colors.
+impl Lerp for Color {
+    fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color::new(
+            self.r.lerp(&other.r, t),
+            self.g.lerp(&other.g, t),
+            self.b.lerp(&other.b, t),
+            self.a.lerp(&other.a, t)
+        )
+    }
+}
+
+impl From<Vector4> for Color {
+    fn from(vector: Vector4) -> Color {
+        Color::new(vector.x, vector.y, vector.z, vector.w)
+    }
+}
+
+impl From<Color> for Vector4 {
+    fn from(color: Color) -> Vector4 {
+        Vector4::new(color.r, color.g, color.b, color.a)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+// The sRGB transfer function from IEC 61966-2-1, which has a short linear part near black instead of a pure 2.2 gamma curve.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        math::powf((value + 0.055) / 1.055, 2.4)
+    }
+}
+
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * math::powf(value, 1.0 / 2.4) - 0.055
+    }
+}
+
+// The red, green and blue of a fully saturated hue with the given chroma, before the lightness is added.
+fn hue_to_rgb(hue: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    }
+}
+
+fn rgb_to_hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma <= 0.0 {
+        return 0.0;
+    }
+
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+
+    sector * 60.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    #[test]
+    fn should_round_trip_every_byte_when_converting_between_srgb_and_linear() {
+        for byte in 0..=255u8 {
+            // Act
+            let color = Color::from_srgb8(byte, byte, byte, byte);
+
+            // Assert
+            assert_eq!(color.to_srgb8(), [byte, byte, byte, byte]);
+        }
+
+        // The middle gray of a paint program is only about a fifth of the light of white.
+        assert!((Color::from_srgb8(128, 128, 128, 255).r - 0.2158605).abs() < 1e-6);
+        assert_eq!(Color::from_srgb(0.5, 0.5, 0.5, 0.5).a, 0.5);
+    }
+
+    #[test]
+    fn should_parse_hex_with_and_without_alpha() {
+        // Act
+        let flame = Color::from_hex("#FF8000").unwrap();
+        let glass = Color::from_hex("3366ccaa").unwrap();
+
+        // Assert
+        assert_eq!(flame.to_srgb8(), [255, 128, 0, 255]);
+        assert_eq!(glass.to_srgb8(), [0x33, 0x66, 0xCC, 0xAA]);
+        assert_eq!(glass.to_hex(), "#3366CCAA");
+        assert_eq!(format!("{}", flame), "#FF8000FF");
+        assert!(Color::from_hex("#FF80").is_none());
+        assert!(Color::from_hex("#+F8000").is_none());
+        assert!(Color::from_hex("#GG8000").is_none());
+    }
+
+    #[test]
+    fn should_place_red_in_lowest_byte_when_packing_rgba8() {
+        // Arrange
+        let color = Color::from_srgb8(0x11, 0x22, 0x33, 0x44);
+
+        // Act
+        let rgba = color.pack_rgba8();
+        let bgra = color.pack_bgra8();
+
+        // Assert
+        assert_eq!(rgba, 0x44332211);
+        assert_eq!(bgra, 0x44112233);
+        assert_eq!(Color::unpack_rgba8(rgba), color);
+        assert_eq!(Color::unpack_bgra8(bgra), color);
+    }
+
+    #[test]
+    fn should_match_known_colors_when_converting_hsv_and_hsl() {
+        // Arrange
+        let orange = Color::from_hex("#FF8000").unwrap();
+
+        // Act
+        let (hue, saturation, value) = orange.to_hsv();
+        let (hsl_hue, hsl_saturation, lightness) = Color::from_hex("#336699").unwrap().to_hsl();
+
+        // Assert
+        assert!(hue.approx_eq_eps(&30.1, 1e-3));
+        assert!(saturation.approx_eq(&1.0));
+        assert!(value.approx_eq(&1.0));
+        assert!(Color::from_hsv(120.0, 1.0, 1.0, 1.0).approx_eq(&Color::new(0.0, 1.0, 0.0, 1.0)));
+        assert!(Color::from_hsv(-120.0, 1.0, 1.0, 1.0).approx_eq(&Color::new(0.0, 0.0, 1.0, 1.0)));
+        assert!(hsl_hue.approx_eq(&210.0));
+        assert!(hsl_saturation.approx_eq(&0.5));
+        assert!(lightness.approx_eq(&0.4));
+        assert!(Color::from_hsl(hsl_hue, hsl_saturation, lightness, 1.0).approx_eq(&Color::from_hex("#336699").unwrap()));
+    }
+
+    #[test]
+    fn should_give_same_result_when_blending_straight_and_premultiplied() {
+        // Arrange
+        let smoke = Color::new(0.8, 0.6, 0.4, 0.25);
+        let sky = Color::new(0.2, 0.4, 0.9, 1.0);
+
+        // Act
+        let straight = smoke.blend(&sky, BlendMode::Alpha);
+        let premultiplied = smoke.premultiply().blend(&sky.premultiply(), BlendMode::Premultiplied).unpremultiply();
+
+        // Assert
+        assert!(straight.approx_eq(&Color::new(0.35, 0.45, 0.775, 1.0)));
+        assert!(premultiplied.approx_eq(&straight));
+        assert!(Color::new(0.5, 0.5, 0.5, 1.0).blend(&sky, BlendMode::Multiply).approx_eq(&Color::new(0.1, 0.2, 0.45, 1.0)));
+        assert!(Color::new(0.5, 0.5, 0.5, 1.0).blend(&sky, BlendMode::Screen).approx_eq(&Color::new(0.6, 0.7, 0.95, 1.0)));
+        assert!(Color::new(1.0, 0.5, 0.0, 0.5).blend(&Color::BLACK, BlendMode::Additive).approx_eq(&Color::new(0.5, 0.25, 0.0, 1.0)));
+        assert_eq!(Color::TRANSPARENT.unpremultiply(), Color::TRANSPARENT);
+    }
+}
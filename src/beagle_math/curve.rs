@@ -0,0 +1,344 @@
+use crate::beagle_math::Vector3;
+
+// Number of straight pieces each segment is split into when measuring the length of a spline.
+// Camera paths and trajectories bend gently, so this keeps the error of the arc-length well below a millimetre per metre.
+const ARC_LENGTH_SAMPLES_PER_SEGMENT: usize = 32;
+
+// Number of points tested on each segment before refining the closest point with Newton's method.
+const CLOSEST_POINT_SAMPLES_PER_SEGMENT: usize = 16;
+const CLOSEST_POINT_ITERATIONS: usize = 8;
+
+// A curve made of cubic segments, used for camera fly-bys and for drawing the predicted descent of the lander.
+// Whatever kind of spline it is created as, every segment is stored as the four control points of a cubic Bezier curve,
+// so evaluation, arc-length and closest-point queries work the same for all of them.
+// The parameter t goes from 0.0 at the start of the first segment to 1.0 at the end of the last one, with each segment
+// taking an equal part of it. The speed along the curve is therefore not constant; use the "_at_distance" methods for that.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spline {
+    segments: Vec<[Vector3; 4]>,
+    // The length of the curve from the start to each of the evenly spaced parameter samples.
+    arc_lengths: Vec<f32>
+}
+
+impl Spline {
+    // A chain of cubic Bezier curves, where the last control point of each segment is the first of the next one.
+    // Returns None unless there are 3n + 1 control points, for n segments.
+    pub fn bezier(control_points: &[Vector3]) -> Option<Spline> {
+        if control_points.len() < 4 || control_points.len() % 3 != 1 {
+            return None;
+        }
+
+        let segments = control_points
+            .windows(4)
+            .step_by(3)
+            .map(|points| [points[0], points[1], points[2], points[3]])
+            .collect();
+
+        Some(Spline::from_segments(segments))
+    }
+
+    // A curve passing through every point with the given tangent (the velocity over one segment).
+    // Returns None if there are fewer than two points, or not one tangent per point.
+    pub fn hermite(points: &[Vector3], tangents: &[Vector3]) -> Option<Spline> {
+        if points.len() < 2 || points.len() != tangents.len() {
+            return None;
+        }
+
+        let segments = (0..points.len() - 1)
+            .map(|i| [points[i], points[i] + tangents[i] / 3.0, points[i + 1] - tangents[i + 1] / 3.0, points[i + 1]])
+            .collect();
+
+        Some(Spline::from_segments(segments))
+    }
+
+    // A curve passing through every point, with the tangents picked from the neighbouring points.
+    // This is the easiest way to create a smooth path, as only the points to pass through are needed.
+    // The first and last points are repeated to give the end segments a neighbour. Returns None if there are fewer than two points.
+    pub fn catmull_rom(points: &[Vector3]) -> Option<Spline> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let last = points.len() - 1;
+        let tangents: Vec<Vector3> = (0..points.len())
+            .map(|i| (points[(i + 1).min(last)] - points[i.saturating_sub(1)]) * 0.5)
+            .collect();
+
+        Spline::hermite(points, &tangents)
+    }
+
+    fn from_segments(segments: Vec<[Vector3; 4]>) -> Spline {
+        let mut spline = Spline {
+            segments,
+            arc_lengths: Vec::new()
+        };
+
+        let sample_count = spline.segments.len() * ARC_LENGTH_SAMPLES_PER_SEGMENT;
+        let mut length = 0.0;
+        let mut previous = spline.point(0.0);
+
+        spline.arc_lengths.push(0.0);
+
+        for i in 1..=sample_count {
+            let point = spline.point(i as f32 / sample_count as f32);
+            length += previous.distance(&point);
+            previous = point;
+
+            spline.arc_lengths.push(length);
+        }
+
+        spline
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn point(&self, t: f32) -> Vector3 {
+        let (segment, u) = self.locate(t);
+        bezier_point(&self.segments[segment], u)
+    }
+
+    // The derivative of the curve with respect to t, whose length is the speed the curve is traversed with.
+    pub fn derivative(&self, t: f32) -> Vector3 {
+        let (segment, u) = self.locate(t);
+        bezier_derivative(&self.segments[segment], u) * self.segments.len() as f32
+    }
+
+    // The unit length direction of the curve at t.
+    pub fn tangent(&self, t: f32) -> Vector3 {
+        let (segment, u) = self.locate(t);
+        let derivative = bezier_derivative(&self.segments[segment], u);
+
+        // Where control points coincide the derivative is zero, and the direction is given by the second derivative instead.
+        if derivative.length_squared() > 1e-12 {
+            derivative.normalize()
+        } else {
+            bezier_second_derivative(&self.segments[segment], u).normalize()
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.arc_lengths.last().unwrap()
+    }
+
+    // The parameter t at the given distance along the curve, found in the table of arc-lengths.
+    // Distances outside the curve are clamped to its ends, and a NaN distance gives a NaN parameter.
+    pub fn parameter_at_distance(&self, distance: f32) -> f32 {
+        if distance.is_nan() {
+            return f32::NAN;
+        }
+
+        let distance = distance.clamp(0.0, self.length());
+        let sample_count = self.arc_lengths.len() - 1;
+
+        // The index of the first sample at or beyond the distance.
+        let index = match self.arc_lengths.binary_search_by(|length| length.total_cmp(&distance)) {
+            Ok(index) => return index as f32 / sample_count as f32,
+            Err(index) => index.max(1)
+        };
+
+        let start = self.arc_lengths[index - 1];
+        let end = self.arc_lengths[index];
+        let fraction = if end > start { (distance - start) / (end - start) } else { 0.0 };
+
+        ((index - 1) as f32 + fraction) / sample_count as f32
+    }
+
+    // Moving the distance forward by a constant amount every frame moves along the curve with constant speed.
+    pub fn point_at_distance(&self, distance: f32) -> Vector3 {
+        self.point(self.parameter_at_distance(distance))
+    }
+
+    pub fn tangent_at_distance(&self, distance: f32) -> Vector3 {
+        self.tangent(self.parameter_at_distance(distance))
+    }
+
+    // The parameter t of the point on the curve closest to "point", together with that point.
+    // Each segment is sampled to find a starting guess, which is then refined with Newton's method
+    // on the derivative of the squared distance.
+    pub fn closest_point(&self, point: &Vector3) -> (f32, Vector3) {
+        let mut best_segment = 0;
+        let mut best_u = 0.0;
+        let mut best_distance = f32::INFINITY;
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            for sample in 0..=CLOSEST_POINT_SAMPLES_PER_SEGMENT {
+                let u = sample as f32 / CLOSEST_POINT_SAMPLES_PER_SEGMENT as f32;
+                let distance = bezier_point(segment, u).distance(point);
+
+                if distance < best_distance {
+                    best_segment = index;
+                    best_u = u;
+                    best_distance = distance;
+                }
+            }
+        }
+
+        let segment = &self.segments[best_segment];
+
+        for _ in 0..CLOSEST_POINT_ITERATIONS {
+            let offset = bezier_point(segment, best_u) - *point;
+            let first = bezier_derivative(segment, best_u);
+            let second = bezier_second_derivative(segment, best_u);
+
+            let slope = offset.dot(&first);
+            let curvature = first.dot(&first) + offset.dot(&second);
+
+            if curvature.abs() < 1e-12 {
+                break;
+            }
+
+            best_u = (best_u - slope / curvature).clamp(0.0, 1.0);
+        }
+
+        let t = (best_segment as f32 + best_u) / self.segments.len() as f32;
+
+        (t, bezier_point(segment, best_u))
+    }
+
+    // The segment containing t, and the position within it in [0, 1].
+    fn locate(&self, t: f32) -> (usize, f32) {
+        let scaled = t.clamp(0.0, 1.0) * self.segments.len() as f32;
+        let segment = (scaled as usize).min(self.segments.len() - 1);
+
+        (segment, scaled - segment as f32)
+    }
+}
+
+fn bezier_point(p: &[Vector3; 4], u: f32) -> Vector3 {
+    let v = 1.0 - u;
+
+    p[0] * (v * v * v) + p[1] * (3.0 * v * v * u) + p[2] * (3.0 * v * u * u) + p[3] * (u * u * u)
+}
+
+fn bezier_derivative(p: &[Vector3; 4], u: f32) -> Vector3 {
+    let v = 1.0 - u;
+
+    (p[1] - p[0]) * (3.0 * v * v) + (p[2] - p[1]) * (6.0 * v * u) + (p[3] - p[2]) * (3.0 * u * u)
+}
+
+fn bezier_second_derivative(p: &[Vector3; 4], u: f32) -> Vector3 {
+    (p[2] - p[1] * 2.0 + p[0]) * (6.0 * (1.0 - u)) + (p[3] - p[2] * 2.0 + p[1]) * (6.0 * u)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    fn approach_path() -> Vec<Vector3> {
+        vec![
+            Vector3::new(0.0, 100.0, 0.0),
+            Vector3::new(20.0, 60.0, 10.0),
+            Vector3::new(30.0, 20.0, 40.0),
+            Vector3::new(35.0, 0.0, 50.0)
+        ]
+    }
+
+    #[test]
+    fn should_pass_through_every_point_when_using_catmull_rom() {
+        // Arrange
+        let points = approach_path();
+
+        // Act
+        let spline = Spline::catmull_rom(&points).unwrap();
+
+        // Assert
+        assert_eq!(spline.segment_count(), 3);
+        for (i, point) in points.iter().enumerate() {
+            assert!(spline.point(i as f32 / 3.0).approx_eq_eps(point, 1e-4));
+        }
+    }
+
+    #[test]
+    fn should_match_hermite_endpoints_and_tangents() {
+        // Arrange
+        let points = [Vector3::new(0.0, 0.0, 0.0), Vector3::new(10.0, 0.0, 0.0)];
+        let tangents = [Vector3::new(0.0, 30.0, 0.0), Vector3::new(0.0, -30.0, 0.0)];
+
+        // Act
+        let spline = Spline::hermite(&points, &tangents).unwrap();
+
+        // Assert
+        assert!(spline.point(1.0).approx_eq(&points[1]));
+        assert!(spline.derivative(0.0).approx_eq(&tangents[0]));
+        assert!(spline.tangent(1.0).approx_eq(&Vector3::new(0.0, -1.0, 0.0)));
+        assert!(spline.point(0.5).y > 0.0);
+    }
+
+    #[test]
+    fn should_reject_wrong_number_of_control_points() {
+        // Arrange
+        let points = approach_path();
+
+        // Assert
+        assert!(Spline::bezier(&points).is_some());
+        assert!(Spline::bezier(&points[..3]).is_none());
+        assert!(Spline::hermite(&points, &points[..2]).is_none());
+        assert!(Spline::catmull_rom(&points[..1]).is_none());
+    }
+
+    #[test]
+    fn should_measure_length_of_straight_bezier() {
+        // Arrange
+        let spline = Spline::bezier(&[
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(9.0, 0.0, 0.0),
+            Vector3::new(10.0, 0.0, 0.0)
+        ]).unwrap();
+
+        // Act
+        let quarter = spline.point_at_distance(2.5);
+
+        // Assert
+        assert!((spline.length() - 10.0).abs() < 1e-4);
+        assert!(quarter.approx_eq_eps(&Vector3::new(2.5, 0.0, 0.0), 1e-2));
+        // The control points bunch up the parameter at the ends, so t = 0.25 is not a quarter of the way.
+        assert!(spline.point(0.25).x < 2.0);
+    }
+
+    #[test]
+    fn should_clamp_distance_and_propagate_nan_when_finding_parameter() {
+        // Arrange
+        let spline = Spline::catmull_rom(&approach_path()).unwrap();
+
+        // Act & Assert
+        assert_eq!(spline.parameter_at_distance(-5.0), 0.0);
+        assert_eq!(spline.parameter_at_distance(spline.length() + 5.0), 1.0);
+        assert!(spline.parameter_at_distance(f32::NAN).is_nan());
+        assert!(spline.point_at_distance(f32::NAN).x.is_nan());
+    }
+
+    #[test]
+    fn should_move_with_constant_speed_when_stepping_by_distance() {
+        // Arrange
+        let spline = Spline::catmull_rom(&approach_path()).unwrap();
+        let steps = 50;
+        let step = spline.length() / steps as f32;
+
+        // Act
+        let points: Vec<Vector3> = (0..=steps).map(|i| spline.point_at_distance(i as f32 * step)).collect();
+
+        // Assert
+        for pair in points.windows(2) {
+            assert!((pair[0].distance(&pair[1]) - step).abs() < step * 0.01);
+        }
+        assert!(points[steps].approx_eq_eps(&Vector3::new(35.0, 0.0, 50.0), 1e-4));
+    }
+
+    #[test]
+    fn should_find_closest_point_on_curve() {
+        // Arrange
+        let spline = Spline::catmull_rom(&approach_path()).unwrap();
+        let on_curve = spline.point(0.6);
+        let normal = spline.tangent(0.6).cross(&Vector3::new(0.0, 1.0, 0.0)).normalize();
+
+        // Act
+        let (t, closest) = spline.closest_point(&(on_curve + normal * 0.5));
+
+        // Assert
+        assert!((t - 0.6).abs() < 1e-3);
+        assert!(closest.approx_eq_eps(&on_curve, 1e-3));
+    }
+}
@@ -0,0 +1,512 @@
+// The constants are copied digit for digit from fdlibm, so they can be compared with the original.
+#![allow(clippy::excessive_precision)]
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, LN_2, PI};
+
+use crate::beagle_math::Real;
+
+// Software implementations of the transcendental functions, giving bit-identical results on every compiler, CPU and OS.
+// IEEE 754 requires +, -, *, / and sqrt to be correctly rounded, so they give the same bits everywhere, but it makes no such
+// promise for sin, cos, exp and friends. The standard library calls the C library or LLVM intrinsics for those, which differ
+// in the last bits between platforms, and a lockstep simulation or a replay drifts apart as soon as one bit differs.
+// Everything here is computed in f64 using only the correctly rounded operations, and rounded to the requested type at the end.
+// The results are within a few ulps of the standard library (and correctly rounded for f32 in almost every case), but slower.
+// For f64, the precision of sin, cos and tan drops slowly for angles larger than about 1e6 radians.
+//
+// With the "deterministic" cargo feature, beagle_math uses these functions internally (through the "math" module below),
+// and the f32 matrix kernels use the scalar code instead of SSE2, so the results do not depend on the CPU architecture either.
+// Simulation code should call the functions in "math" instead of the methods on f32, to follow the same feature.
+
+pub fn sin<T: Real>(x: T) -> T {
+    T::from_f64(sin_cos_f64(x.into_f64()).0)
+}
+
+pub fn cos<T: Real>(x: T) -> T {
+    T::from_f64(sin_cos_f64(x.into_f64()).1)
+}
+
+pub fn sin_cos<T: Real>(x: T) -> (T, T) {
+    let (sin, cos) = sin_cos_f64(x.into_f64());
+    (T::from_f64(sin), T::from_f64(cos))
+}
+
+pub fn tan<T: Real>(x: T) -> T {
+    let (sin, cos) = sin_cos_f64(x.into_f64());
+    T::from_f64(sin / cos)
+}
+
+pub fn asin<T: Real>(x: T) -> T {
+    let x = x.into_f64();
+    T::from_f64(atan2_f64(x, ((1.0 - x) * (1.0 + x)).sqrt()))
+}
+
+pub fn acos<T: Real>(x: T) -> T {
+    let x = x.into_f64();
+    T::from_f64(atan2_f64(((1.0 - x) * (1.0 + x)).sqrt(), x))
+}
+
+pub fn atan<T: Real>(x: T) -> T {
+    T::from_f64(atan_f64(x.into_f64()))
+}
+
+pub fn atan2<T: Real>(y: T, x: T) -> T {
+    T::from_f64(atan2_f64(y.into_f64(), x.into_f64()))
+}
+
+pub fn exp<T: Real>(x: T) -> T {
+    T::from_f64(exp_f64(x.into_f64()))
+}
+
+pub fn ln<T: Real>(x: T) -> T {
+    T::from_f64(ln_f64(x.into_f64()))
+}
+
+// x^y, computed as exp(y * ln(x)), or by multiplying for small integer powers. A negative x is only allowed with an integer y, like powf.
+pub fn powf<T: Real>(x: T, y: T) -> T {
+    T::from_f64(powf_f64(x.into_f64(), y.into_f64()))
+}
+
+// The functions used by beagle_math itself: the software versions with the "deterministic" feature,
+// and otherwise the faster ones from the standard library.
+#[cfg(feature = "deterministic")]
+pub mod math {
+    pub use super::{acos, asin, atan, atan2, cos, exp, ln, powf, sin, sin_cos, tan};
+}
+
+#[cfg(not(feature = "deterministic"))]
+pub mod math {
+    use crate::beagle_math::Real;
+
+    pub fn sin<T: Real>(x: T) -> T {
+        x.sin()
+    }
+
+    pub fn cos<T: Real>(x: T) -> T {
+        x.cos()
+    }
+
+    pub fn sin_cos<T: Real>(x: T) -> (T, T) {
+        x.sin_cos()
+    }
+
+    pub fn tan<T: Real>(x: T) -> T {
+        x.tan()
+    }
+
+    pub fn asin<T: Real>(x: T) -> T {
+        x.asin()
+    }
+
+    pub fn acos<T: Real>(x: T) -> T {
+        x.acos()
+    }
+
+    pub fn atan<T: Real>(x: T) -> T {
+        x.atan()
+    }
+
+    pub fn atan2<T: Real>(y: T, x: T) -> T {
+        y.atan2(x)
+    }
+
+    pub fn exp<T: Real>(x: T) -> T {
+        x.exp()
+    }
+
+    pub fn ln<T: Real>(x: T) -> T {
+        x.ln()
+    }
+
+    pub fn powf<T: Real>(x: T, y: T) -> T {
+        x.powf(y)
+    }
+}
+
+// The polynomials and constants below are the ones from fdlibm (https://www.netlib.org/fdlibm/), which is where most
+// C libraries got theirs from.
+
+// pi / 2 split in two, where the first part has so few bits that multiplying it with the quadrant number is exact.
+const FRAC_PI_2_HIGH: f64 = 1.57079632673412561417e+00;
+const FRAC_PI_2_LOW: f64 = 6.07710050650619224932e-11;
+
+const SIN_COEFFICIENTS: [f64; 6] = [
+    -1.66666666666666324348e-01,
+    8.33333333332248946124e-03,
+    -1.98412698298579493134e-04,
+    2.75573137070700676789e-06,
+    -2.50507602534068634195e-08,
+    1.58969099521155010221e-10
+];
+
+const COS_COEFFICIENTS: [f64; 6] = [
+    4.16666666666666019037e-02,
+    -1.38888888888741095749e-03,
+    2.48015872894767294178e-05,
+    -2.75573143513906633035e-07,
+    2.08757232129817482790e-09,
+    -1.13596475577881948265e-11
+];
+
+// Evaluates the polynomial with Horner's method. The order of the operations is fixed, which is what makes it deterministic.
+fn polynomial(x: f64, coefficients: &[f64]) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, coefficient| sum * x + coefficient)
+}
+
+fn sin_cos_f64(x: f64) -> (f64, f64) {
+    if !x.is_finite() {
+        return (f64::NAN, f64::NAN);
+    }
+
+    // Moves the angle into [-pi/4, pi/4], keeping track of which quarter of the circle it was in.
+    let quadrant = (x * (2.0 / PI)).round();
+    let r = (x - quadrant * FRAC_PI_2_HIGH) - quadrant * FRAC_PI_2_LOW;
+
+    let z = r * r;
+    let sin = r + r * z * polynomial(z, &SIN_COEFFICIENTS);
+    let cos = 1.0 - 0.5 * z + z * z * polynomial(z, &COS_COEFFICIENTS);
+
+    match (quadrant as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin)
+    }
+}
+
+// atan(0.5), atan(1.0), atan(1.5) and atan(infinity), each split into a high and a low part.
+const ATAN_HIGH: [f64; 4] = [4.63647609000806093515e-01, FRAC_PI_4, 9.82793723247329054082e-01, FRAC_PI_2];
+const ATAN_LOW: [f64; 4] = [2.26987774529616870924e-17, 3.06161699786838301793e-17, 1.39033110312309984516e-17, 6.12323399573676603587e-17];
+
+const ATAN_EVEN_COEFFICIENTS: [f64; 6] = [
+    3.33333333333329318027e-01,
+    1.42857142725034663711e-01,
+    9.09088713343650656196e-02,
+    6.66107313738753120669e-02,
+    4.97687799461593236017e-02,
+    1.62858201153657823623e-02
+];
+
+const ATAN_ODD_COEFFICIENTS: [f64; 5] = [
+    -1.99999999998764832476e-01,
+    -1.11111104054623557880e-01,
+    -7.69187620504482999495e-02,
+    -5.83357013379057348645e-02,
+    -3.65315727442169155270e-02
+];
+
+fn atan_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+
+    let magnitude = x.abs();
+
+    if magnitude >= 7.378697629483821e19 {
+        return (ATAN_HIGH[3] + ATAN_LOW[3]).copysign(x);
+    }
+
+    // Picks a known angle close to the input, so the polynomial only has to cover the small difference.
+    let (index, t) = if magnitude < 0.4375 {
+        (None, magnitude)
+    } else if magnitude < 0.6875 {
+        (Some(0), (2.0 * magnitude - 1.0) / (2.0 + magnitude))
+    } else if magnitude < 1.1875 {
+        (Some(1), (magnitude - 1.0) / (magnitude + 1.0))
+    } else if magnitude < 2.4375 {
+        (Some(2), (magnitude - 1.5) / (1.0 + 1.5 * magnitude))
+    } else {
+        (Some(3), -1.0 / magnitude)
+    };
+
+    let z = t * t;
+    let w = z * z;
+    let even = z * polynomial(w, &ATAN_EVEN_COEFFICIENTS);
+    let odd = w * polynomial(w, &ATAN_ODD_COEFFICIENTS);
+
+    let angle = match index {
+        None => t - t * (even + odd),
+        Some(i) => ATAN_HIGH[i] - ((t * (even + odd) - ATAN_LOW[i]) - t)
+    };
+
+    angle.copysign(x)
+}
+
+fn atan2_f64(y: f64, x: f64) -> f64 {
+    if x.is_nan() || y.is_nan() {
+        return f64::NAN;
+    }
+
+    // Both infinite gives one of the diagonals, where y / x would be NaN.
+    if x.is_infinite() && y.is_infinite() {
+        let angle = if x > 0.0 { FRAC_PI_4 } else { 3.0 * FRAC_PI_4 };
+        return angle.copysign(y);
+    }
+
+    if y == 0.0 {
+        return if x.is_sign_positive() { y } else { PI.copysign(y) };
+    }
+
+    if x == 0.0 {
+        return FRAC_PI_2.copysign(y);
+    }
+
+    let angle = atan_f64((y / x).abs());
+
+    if x > 0.0 {
+        angle.copysign(y)
+    } else {
+        (PI - angle).copysign(y)
+    }
+}
+
+// ln(2) split in two, where the first part has so few bits that multiplying it with the exponent is exact.
+const LN_2_HIGH: f64 = 6.93147180369123816490e-01;
+const LN_2_LOW: f64 = 1.90821492927058770002e-10;
+
+// 1 / n! for the Taylor series of exp, which is enough for full precision when |r| <= ln(2) / 2.
+const EXP_COEFFICIENTS: [f64; 14] = [
+    1.0,
+    1.0,
+    1.0 / 2.0,
+    1.0 / 6.0,
+    1.0 / 24.0,
+    1.0 / 120.0,
+    1.0 / 720.0,
+    1.0 / 5040.0,
+    1.0 / 40320.0,
+    1.0 / 362880.0,
+    1.0 / 3628800.0,
+    1.0 / 39916800.0,
+    1.0 / 479001600.0,
+    1.0 / 6227020800.0
+];
+
+fn exp_f64(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+
+    if x > 709.8 {
+        return f64::INFINITY;
+    }
+
+    if x < -745.2 {
+        return 0.0;
+    }
+
+    // exp(x) = 2^k * exp(r), with r in [-ln(2)/2, ln(2)/2].
+    let k = (x / LN_2).round();
+    let r = (x - k * LN_2_HIGH) - k * LN_2_LOW;
+
+    scale_by_power_of_two(polynomial(r, &EXP_COEFFICIENTS), k as i32)
+}
+
+// Multiplies by 2^exponent in steps that each stay within the range of a normal f64, so every step is exact
+// (except for the rounding of a subnormal result, which IEEE 754 also defines exactly).
+fn scale_by_power_of_two(mut value: f64, mut exponent: i32) -> f64 {
+    while exponent > 1000 {
+        value *= power_of_two(1000);
+        exponent -= 1000;
+    }
+
+    while exponent < -1000 {
+        value *= power_of_two(-1000);
+        exponent += 1000;
+    }
+
+    value * power_of_two(exponent)
+}
+
+fn power_of_two(exponent: i32) -> f64 {
+    f64::from_bits(((exponent + 1023) as u64) << 52)
+}
+
+fn ln_f64(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+
+    if x.is_infinite() {
+        return x;
+    }
+
+    // Subnormals are scaled up first, so the exponent can be read from the bits.
+    let (x, mut exponent) = if x < f64::MIN_POSITIVE { (x * power_of_two(54), -54) } else { (x, 0) };
+
+    // Splits x into mantissa * 2^exponent, with the mantissa in [sqrt(1/2), sqrt(2)).
+    let bits = x.to_bits();
+    exponent += ((bits >> 52) & 0x7ff) as i32 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+
+    if mantissa > std::f64::consts::SQRT_2 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+
+    // ln(m) = 2 * atanh(s) = 2 * (s + s^3/3 + s^5/5 + ...), with s = (m - 1) / (m + 1) in [-0.172, 0.172].
+    let s = (mantissa - 1.0) / (mantissa + 1.0);
+    let z = s * s;
+    let series = (1..12).rev().fold(0.0, |sum, n| sum * z + 1.0 / (2 * n + 1) as f64);
+    let ln_mantissa = 2.0 * s + 2.0 * s * z * series;
+
+    exponent as f64 * LN_2_HIGH + (exponent as f64 * LN_2_LOW + ln_mantissa)
+}
+
+fn powf_f64(x: f64, y: f64) -> f64 {
+    if y == 0.0 {
+        return 1.0;
+    }
+
+    if x.is_nan() || y.is_nan() {
+        return f64::NAN;
+    }
+
+    if x == 0.0 {
+        return if y > 0.0 { 0.0 } else { f64::INFINITY };
+    }
+
+    // Small integer powers are multiplied out, which is exact where exp(y * ln(x)) would be off in the last bits.
+    if y.fract() == 0.0 && y.abs() <= 64.0 {
+        let mut result = 1.0;
+        let mut base = x;
+        let mut exponent = y.abs() as u32;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+
+            base *= base;
+            exponent >>= 1;
+        }
+
+        return if y < 0.0 { 1.0 / result } else { result };
+    }
+
+    if x < 0.0 {
+        if y.fract() != 0.0 {
+            return f64::NAN;
+        }
+
+        let magnitude = exp_f64(y * ln_f64(-x));
+        let odd = (y / 2.0).fract() != 0.0;
+
+        return if odd { -magnitude } else { magnitude };
+    }
+
+    exp_f64(y * ln_f64(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    // Evenly spread inputs, including some awkward ones close to multiples of pi / 2.
+    fn inputs(min: f64, max: f64) -> Vec<f64> {
+        let mut values: Vec<f64> = (0..=2000).map(|i| min + (max - min) * i as f64 / 2000.0).collect();
+        values.extend((-8..=8).map(|i| i as f64 * std::f64::consts::FRAC_PI_2));
+        values
+    }
+
+    fn assert_close(name: &str, input: f64, actual: f64, expected: f64) {
+        let tolerance = 4.0 * f64::EPSILON * expected.abs().max(1.0);
+        assert!((actual - expected).abs() <= tolerance, "{}({}) = {}, but std gives {}", name, input, actual, expected);
+    }
+
+    #[test]
+    fn should_match_std_when_computing_f64_functions() {
+        for x in inputs(-100.0, 100.0) {
+            // Assert
+            assert_close("sin", x, deterministic::sin(x), x.sin());
+            assert_close("cos", x, deterministic::cos(x), x.cos());
+            assert_close("atan", x, deterministic::atan(x), x.atan());
+            assert_close("atan2", x, deterministic::atan2(x, 3.0), x.atan2(3.0));
+            assert_close("atan2", x, deterministic::atan2(-3.0, x), (-3.0f64).atan2(x));
+            assert_close("exp", x, deterministic::exp(x / 10.0), (x / 10.0).exp());
+            assert_close("ln", x, deterministic::ln(x.abs() * 1e3 + 1e-300), (x.abs() * 1e3 + 1e-300).ln());
+        }
+
+        for x in inputs(-1.0, 1.0).into_iter().filter(|x| x.abs() <= 1.0) {
+            assert_close("asin", x, deterministic::asin(x), x.asin());
+            assert_close("acos", x, deterministic::acos(x), x.acos());
+            assert_close("tan", x, deterministic::tan(x), x.tan());
+            assert_close("powf", x, deterministic::powf(x.abs() * 5.0, 2.4), (x.abs() * 5.0).powf(2.4));
+        }
+    }
+
+    #[test]
+    fn should_be_within_one_ulp_of_std_when_computing_f32_functions() {
+        for x in inputs(-20.0, 20.0).into_iter().map(|x| x as f32) {
+            // Assert
+            assert!(deterministic::sin(x).approx_eq_ulps(&x.sin(), 1), "sin({})", x);
+            assert!(deterministic::cos(x).approx_eq_ulps(&x.cos(), 1), "cos({})", x);
+            assert!(deterministic::atan2(x, -1.5).approx_eq_ulps(&x.atan2(-1.5), 1), "atan2({})", x);
+            assert!(deterministic::exp(x).approx_eq_ulps(&x.exp(), 1), "exp({})", x);
+            assert!(deterministic::powf(2.0f32, x).approx_eq_ulps(&2.0f32.powf(x), 1), "powf({})", x);
+        }
+    }
+
+    #[test]
+    fn should_handle_special_values_like_std() {
+        // Assert
+        assert!(deterministic::sin(f64::INFINITY).is_nan());
+        assert!(deterministic::ln(-1.0f64).is_nan());
+        assert!(deterministic::acos(1.5f32).is_nan());
+        assert!(deterministic::powf(-2.0f64, 0.5).is_nan());
+        assert_eq!(deterministic::ln(0.0f64), f64::NEG_INFINITY);
+        assert_eq!(deterministic::exp(1000.0f64), f64::INFINITY);
+        assert_eq!(deterministic::exp(-1000.0f64), 0.0);
+        assert_eq!(deterministic::powf(-2.0f64, 3.0), -8.0);
+        assert_eq!(deterministic::powf(0.0f32, 0.0), 1.0);
+        assert_eq!(deterministic::atan2(0.0f64, -1.0), std::f64::consts::PI);
+        assert_eq!(deterministic::atan2(f64::INFINITY, f64::NEG_INFINITY), 3.0 * std::f64::consts::FRAC_PI_4);
+        assert_eq!(deterministic::sin(0.0f32), 0.0);
+        assert_eq!(deterministic::cos(0.0f32), 1.0);
+    }
+
+    #[test]
+    fn should_give_same_bits_on_every_platform() {
+        // Arrange
+        // A hash of the bits of many results. These numbers were computed once, and any platform giving a different hash
+        // would simulate differently. Change them only together with a deliberate change of the algorithms.
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut add = |value: f32| {
+            hash = (hash ^ value.to_bits() as u64).wrapping_mul(0x100000001b3);
+        };
+
+        // Act
+        for i in 0..10000 {
+            let x = (i as f32 - 5000.0) * 0.01;
+
+            add(deterministic::sin(x));
+            add(deterministic::cos(x));
+            add(deterministic::tan(x));
+            add(deterministic::atan2(x, 0.7));
+            add(deterministic::exp(x * 0.1));
+            add(deterministic::ln(x.abs() + 0.001));
+            add(deterministic::powf(x.abs(), 1.7));
+        }
+
+        // Assert
+        assert_eq!(hash, 2199926585962133982);
+    }
+
+    #[cfg(feature = "deterministic")]
+    #[test]
+    fn should_use_software_functions_when_deterministic_feature_is_enabled() {
+        // Arrange
+        let angle = 1.234f32;
+
+        // Act
+        let rotation = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), angle);
+
+        // Assert
+        assert_eq!(rotation.y, deterministic::sin(angle / 2.0));
+        assert_eq!(rotation.w, deterministic::cos(angle / 2.0));
+        assert_eq!(math::atan2(1.0f32, 2.0), deterministic::atan2(1.0f32, 2.0));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::beagle_math::{Lerp, Mat4, Quaternion, Transform, Vector3};
+
+// A rigid transform (a rotation followed by a translation) stored as a dual quaternion: real + dual * e, where e * e = 0.
+// The real part is the rotation, and the dual part is half the translation multiplied with the rotation.
+// Unlike matrices, dual quaternions can be blended as a weighted sum and still give a rigid transform after normalizing,
+// without the shrinking that blending matrices gives. This is what lets the parts of a rig, like the landing legs of the lander,
+// be blended smoothly between poses.
+// Like Quaternion, a.mul(&b) is the transform "a" followed by "b".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DualQuaternion {
+    pub real: Quaternion,
+    pub dual: Quaternion
+}
+
+impl Default for DualQuaternion {
+    fn default() -> DualQuaternion {
+        DualQuaternion::identity()
+    }
+}
+
+impl DualQuaternion {
+    pub fn identity() -> DualQuaternion {
+        DualQuaternion {
+            real: Quaternion::identity(),
+            dual: Quaternion::new(0.0, 0.0, 0.0, 0.0)
+        }
+    }
+
+    // The rotation is expected to be of unit length.
+    pub fn from_rotation_translation(rotation: &Quaternion, translation: &Vector3) -> DualQuaternion {
+        // The translation t as a pure quaternion (t, 0), placed after the rotation.
+        let translation = Quaternion::new(translation.x, translation.y, translation.z, 0.0);
+
+        DualQuaternion {
+            real: *rotation,
+            dual: scale(&rotation.mul(&translation), 0.5)
+        }
+    }
+
+    // The scale of the transform is dropped, since a dual quaternion can only represent rigid transforms.
+    pub fn from_transform(transform: &Transform) -> DualQuaternion {
+        DualQuaternion::from_rotation_translation(&transform.rotation, &transform.translation)
+    }
+
+    pub fn rotation(&self) -> Quaternion {
+        self.real
+    }
+
+    pub fn translation(&self) -> Vector3 {
+        let translation = scale(&self.real.conjugate().mul(&self.dual), 2.0);
+        Vector3::new(translation.x, translation.y, translation.z)
+    }
+
+    // This transform followed by "dual_quaternion".
+    pub fn mul(&self, dual_quaternion: &DualQuaternion) -> DualQuaternion {
+        DualQuaternion {
+            real: self.real.mul(&dual_quaternion.real),
+            dual: add(&self.dual.mul(&dual_quaternion.real), &self.real.mul(&dual_quaternion.dual))
+        }
+    }
+
+    // The inverse of a unit dual quaternion is its conjugate, where both parts are conjugated.
+    pub fn inverse(&self) -> DualQuaternion {
+        DualQuaternion {
+            real: self.real.conjugate(),
+            dual: self.dual.conjugate()
+        }
+    }
+
+    // Scales the real part to unit length, and removes the part of the dual part that would not be a pure translation.
+    pub fn normalize(&self) -> DualQuaternion {
+        let length = self.real.length();
+        let real = scale(&self.real, 1.0 / length);
+        let dual = scale(&self.dual, 1.0 / length);
+
+        DualQuaternion {
+            real,
+            dual: add(&dual, &scale(&real, -real.dot(&dual)))
+        }
+    }
+
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        self.real.rotate_vector(point) + self.translation()
+    }
+
+    pub fn transform_vector(&self, vector: &Vector3) -> Vector3 {
+        self.real.rotate_vector(vector)
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        self.real.to_mat4().mul(&Mat4::translate(&self.translation()))
+    }
+
+    pub fn to_transform(&self) -> Transform {
+        Transform::new(self.translation(), self.real, Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    // Dual quaternion linear blending of several transforms, such as the bones influencing a vertex.
+    // The weights do not have to sum to one. Every dual quaternion is flipped to the same hemisphere as the first one,
+    // since q and -q are the same transform, but would cancel each other out in the sum.
+    // Returns None if there are no transforms, or the weights cancel out.
+    pub fn blend(weighted: &[(DualQuaternion, f32)]) -> Option<DualQuaternion> {
+        let (first, _) = weighted.first()?;
+        let mut sum = DualQuaternion {
+            real: Quaternion::new(0.0, 0.0, 0.0, 0.0),
+            dual: Quaternion::new(0.0, 0.0, 0.0, 0.0)
+        };
+
+        for (dual_quaternion, weight) in weighted {
+            let weight = if first.real.dot(&dual_quaternion.real) < 0.0 { -weight } else { *weight };
+
+            sum.real = add(&sum.real, &scale(&dual_quaternion.real, weight));
+            sum.dual = add(&sum.dual, &scale(&dual_quaternion.dual, weight));
+        }
+
+        if sum.real.length() < 1e-6 {
+            return None;
+        }
+
+        Some(sum.normalize())
+    }
+}
+
+// Blends between the two transforms, which moves along a screw motion, close to the one with constant speed.
+impl Lerp for DualQuaternion {
+    fn lerp(&self, other: &DualQuaternion, t: f32) -> DualQuaternion {
+        DualQuaternion::blend(&[(*self, 1.0 - t), (*other, t)]).unwrap_or(*self)
+    }
+}
+
+fn add(a: &Quaternion, b: &Quaternion) -> Quaternion {
+    Quaternion::new(a.x + b.x, a.y + b.y, a.z + b.z, a.w + b.w)
+}
+
+fn scale(quat: &Quaternion, factor: f32) -> Quaternion {
+    Quaternion::new(quat.x * factor, quat.y * factor, quat.z * factor, quat.w * factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use crate::beagle_math::*;
+
+    fn leg_pose(angle: f32) -> DualQuaternion {
+        DualQuaternion::from_rotation_translation(&Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), angle), &Vector3::new(1.0, -0.5, 0.0))
+    }
+
+    #[test]
+    fn should_match_transform_when_converting_to_matrix() {
+        // Arrange
+        let transform = Transform::new(Vector3::new(3.0, -2.0, 5.0), Quaternion::from_axis_angle(&Vector3::new(1.0, 1.0, 0.0), 0.7), Vector3::new(1.0, 1.0, 1.0));
+
+        // Act
+        let dual_quaternion = DualQuaternion::from_transform(&transform);
+
+        // Assert
+        assert!(dual_quaternion.to_mat4().approx_eq(&transform.to_mat4()));
+        assert!(dual_quaternion.translation().approx_eq(&transform.translation));
+        assert!(dual_quaternion.transform_point(&Vector3::new(1.0, 2.0, 3.0)).approx_eq(&transform.transform_point(&Vector3::new(1.0, 2.0, 3.0))));
+        assert!(dual_quaternion.to_transform().approx_eq(&transform));
+    }
+
+    #[test]
+    fn should_apply_first_then_second_when_composing() {
+        // Arrange
+        let first = leg_pose(0.4);
+        let second = DualQuaternion::from_rotation_translation(&Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 1.2), &Vector3::new(0.0, 10.0, 0.0));
+        let point = Vector3::new(0.5, 0.0, 2.0);
+
+        // Act
+        let composed = first.mul(&second);
+
+        // Assert
+        assert!(composed.to_mat4().approx_eq(&first.to_mat4().mul(&second.to_mat4())));
+        assert!(composed.transform_point(&point).approx_eq(&second.transform_point(&first.transform_point(&point))));
+        assert!(composed.mul(&composed.inverse()).to_mat4().approx_eq(&Mat4::identity()));
+    }
+
+    #[test]
+    fn should_stay_rigid_when_blending() {
+        // Arrange
+        let stowed = leg_pose(0.0);
+        let deployed = leg_pose(FRAC_PI_2);
+
+        // Act
+        let halfway = stowed.lerp(&deployed, 0.5);
+
+        // Assert
+        // Both poses rotate about the same pivot, so the blend rotates halfway about it too, keeping the foot at its distance.
+        assert!(halfway.approx_eq(&leg_pose(FRAC_PI_2 / 2.0)));
+        assert!(halfway.real.length().approx_eq(&1.0));
+        assert!(halfway.transform_point(&Vector3::new(0.0, -2.0, 0.0)).distance(&halfway.translation()).approx_eq(&2.0));
+    }
+
+    #[test]
+    fn should_handle_opposite_signs_when_blending() {
+        // Arrange
+        let pose = leg_pose(0.3);
+        let negated = DualQuaternion {
+            real: Quaternion::new(-pose.real.x, -pose.real.y, -pose.real.z, -pose.real.w),
+            dual: Quaternion::new(-pose.dual.x, -pose.dual.y, -pose.dual.z, -pose.dual.w)
+        };
+
+        // Act
+        let blended = DualQuaternion::blend(&[(pose, 0.5), (negated, 0.5)]).unwrap();
+
+        // Assert
+        assert!(blended.approx_eq(&pose));
+        assert!(DualQuaternion::blend(&[]).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::beagle_math::{math, Mat4, Quaternion, Vector3};
+
+// The order the three rotations of EulerAngles are applied in, named after the axes with the first rotation first.
+// XYZ means pitch (about X) first, then yaw (about Y), and lastly roll (about Z), all about the fixed axes of the parent space.
+// Applying them in the opposite order about the axes of the object itself gives the same result,
+// so ZXY can also be read as yaw, then pitch about the turned X axis, and lastly roll about the turned Z axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX
+}
+
+impl EulerOrder {
+    // The axes as indices (X = 0, Y = 1, Z = 2), in the order the rotations are applied.
+    fn axes(&self) -> [usize; 3] {
+        match self {
+            EulerOrder::XYZ => [0, 1, 2],
+            EulerOrder::XZY => [0, 2, 1],
+            EulerOrder::YXZ => [1, 0, 2],
+            EulerOrder::YZX => [1, 2, 0],
+            EulerOrder::ZXY => [2, 0, 1],
+            EulerOrder::ZYX => [2, 1, 0]
+        }
+    }
+
+    // 1.0 for the cyclic orders (XYZ, YZX and ZXY), and -1.0 for the others.
+    // The extraction formulas are the same for all orders, except for the signs flipped by this parity.
+    fn parity(&self) -> f32 {
+        match self {
+            EulerOrder::XYZ | EulerOrder::YZX | EulerOrder::ZXY => 1.0,
+            _ => -1.0
+        }
+    }
+}
+
+// An orientation given as three rotations, in radians, which is easier to read and edit than a quaternion.
+// Yaw rotates about the Y (up) axis, pitch about the X (right) axis and roll about the Z (forward) axis.
+// Euler angles should only be used for showing and editing orientations. Interpolating or accumulating them leads to gimbal lock,
+// so convert them to a quaternion for anything else.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EulerAngles {
+    pub order: EulerOrder,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32
+}
+
+// When the cosine of the middle rotation is below this, the first and last axes line up (gimbal lock),
+// and only their combined rotation can be recovered.
+const GIMBAL_LOCK_EPSILON: f32 = 1e-6;
+
+impl EulerAngles {
+    pub fn new(order: EulerOrder, yaw: f32, pitch: f32, roll: f32) -> EulerAngles {
+        EulerAngles {
+            order,
+            yaw,
+            pitch,
+            roll
+        }
+    }
+
+    pub fn from_degrees(order: EulerOrder, yaw: f32, pitch: f32, roll: f32) -> EulerAngles {
+        EulerAngles::new(order, yaw.to_radians(), pitch.to_radians(), roll.to_radians())
+    }
+
+    // The angles in degrees as (yaw, pitch, roll).
+    pub fn to_degrees(&self) -> (f32, f32, f32) {
+        (self.yaw.to_degrees(), self.pitch.to_degrees(), self.roll.to_degrees())
+    }
+
+    pub fn to_quaternion(&self) -> Quaternion {
+        let [first, second, third] = self.order.axes();
+
+        self.axis_rotation(first)
+            .mul(&self.axis_rotation(second))
+            .mul(&self.axis_rotation(third))
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        self.to_quaternion().to_mat4()
+    }
+
+    pub fn from_quaternion(quat: &Quaternion, order: EulerOrder) -> EulerAngles {
+        EulerAngles::from_mat4(&quat.to_mat4(), order)
+    }
+
+    // Extracts the angles from the upper 3x3 part of a matrix, which is expected to be a pure rotation.
+    // The middle angle is always in [-90, 90] degrees, and the first and last angles in [-180, 180] degrees.
+    // In gimbal lock, where the middle angle is +/-90 degrees, the last angle is set to zero and the whole rotation
+    // about the lined up axes is given to the first angle, so the same matrix always gives the same angles.
+    pub fn from_mat4(mat: &Mat4, order: EulerOrder) -> EulerAngles {
+        let [i, j, k] = order.axes();
+        let parity = order.parity();
+
+        // The formulas are written for column vectors, so the matrix is read transposed.
+        let m = |row: usize, column: usize| mat.get(row as i32, column as i32);
+
+        // atan2 stays precise close to +/-90 degrees, where asin of the sine alone would lose most of its precision.
+        let cos_second = (m(k, j) * m(k, j) + m(k, k) * m(k, k)).sqrt();
+        let second = math::atan2(-parity * m(k, i), cos_second);
+
+        let (first, third) = if cos_second > GIMBAL_LOCK_EPSILON {
+            (math::atan2(parity * m(k, j), m(k, k)), math::atan2(parity * m(j, i), m(i, i)))
+        } else {
+            (math::atan2(-parity * m(j, k), m(j, j)), 0.0)
+        };
+
+        let mut angles = Vector3::default();
+        set_axis(&mut angles, i, first);
+        set_axis(&mut angles, j, second);
+        set_axis(&mut angles, k, third);
+
+        EulerAngles::new(order, angles.y, angles.x, angles.z)
+    }
+
+    fn axis_rotation(&self, axis: usize) -> Quaternion {
+        match axis {
+            0 => Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), self.pitch),
+            1 => Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), self.yaw),
+            _ => Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), self.roll)
+        }
+    }
+}
+
+fn set_axis(vector: &mut Vector3, axis: usize, value: f32) {
+    match axis {
+        0 => vector.x = value,
+        1 => vector.y = value,
+        _ => vector.z = value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    const ORDERS: [EulerOrder; 6] = [EulerOrder::XYZ, EulerOrder::XZY, EulerOrder::YXZ, EulerOrder::YZX, EulerOrder::ZXY, EulerOrder::ZYX];
+
+    #[test]
+    fn should_apply_rotations_in_given_order() {
+        // Arrange
+        let yaw = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.4);
+        let pitch = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), -0.9);
+        let roll = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 1.3);
+
+        // Act
+        let xyz = EulerAngles::new(EulerOrder::XYZ, 0.4, -0.9, 1.3).to_quaternion();
+        let zxy = EulerAngles::new(EulerOrder::ZXY, 0.4, -0.9, 1.3).to_quaternion();
+
+        // Assert
+        assert!(xyz.approx_eq(&pitch.mul(&yaw).mul(&roll)));
+        assert!(zxy.approx_eq(&roll.mul(&pitch).mul(&yaw)));
+    }
+
+    #[test]
+    fn should_return_original_angles_when_converting_to_matrix_and_back() {
+        for order in ORDERS.iter() {
+            // Arrange
+            let angles = EulerAngles::from_degrees(*order, 35.0, -60.0, 120.0);
+
+            // Act
+            let from_matrix = EulerAngles::from_mat4(&angles.to_mat4(), *order);
+            let from_quaternion = EulerAngles::from_quaternion(&angles.to_quaternion(), *order);
+
+            // Assert
+            assert!(from_matrix.to_mat4().approx_eq_eps(&angles.to_mat4(), 1e-4), "{:?}", order);
+            assert!(from_quaternion.to_quaternion().dot(&angles.to_quaternion()).abs() > 0.9999, "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn should_keep_angles_within_range_when_extracting() {
+        for order in ORDERS.iter() {
+            // Arrange
+            let angles = EulerAngles::from_degrees(*order, 10.0, 20.0, 30.0);
+
+            // Act
+            let (yaw, pitch, roll) = EulerAngles::from_mat4(&angles.to_mat4(), *order).to_degrees();
+
+            // Assert
+            assert!((yaw - 10.0).abs() < 1e-3 && (pitch - 20.0).abs() < 1e-3 && (roll - 30.0).abs() < 1e-3, "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn should_zero_last_angle_when_in_gimbal_lock() {
+        for order in ORDERS.iter() {
+            // Arrange
+            let [_, middle, _] = order.axes();
+            let (yaw, pitch, roll) = match middle {
+                0 => (25.0, 90.0, 40.0),
+                1 => (-90.0, 25.0, 40.0),
+                _ => (25.0, 40.0, 90.0)
+            };
+            let locked = EulerAngles::from_degrees(*order, yaw, pitch, roll);
+
+            // Act
+            let extracted = EulerAngles::from_mat4(&locked.to_mat4(), *order);
+
+            // Assert
+            let [_, _, last] = order.axes();
+            let last_angle = match last {
+                0 => extracted.pitch,
+                1 => extracted.yaw,
+                _ => extracted.roll
+            };
+
+            assert_eq!(last_angle, 0.0, "{:?}", order);
+            assert!(extracted.to_mat4().approx_eq_eps(&locked.to_mat4(), 1e-4), "{:?}", order);
+        }
+    }
+}
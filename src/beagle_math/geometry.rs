@@ -0,0 +1,542 @@
+use std::fmt;
+
+use crate::beagle_math::{DepthRange, Mat4, Vector3};
+
+// Below this value, a ray is treated as parallel to a plane or triangle.
+const PARALLEL_EPSILON: f32 = 1e-6;
+
+// A plane described by the equation normal.dot(p) + distance = 0.
+// The normal is expected to be of unit length, in which case "signed_distance" gives the real distance to the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub distance: f32
+}
+
+impl Plane {
+    pub fn new(normal: Vector3, distance: f32) -> Plane {
+        Plane {
+            normal,
+            distance
+        }
+    }
+
+    pub fn from_point_normal(point: &Vector3, normal: &Vector3) -> Plane {
+        let normal = normal.normalize();
+
+        Plane::new(normal, -normal.dot(point))
+    }
+
+    // The normal points towards the side from which a, b and c appear in clockwise order,
+    // matching the front faces of the rasterizer in a left-handed coordinate system.
+    pub fn from_points(a: &Vector3, b: &Vector3, c: &Vector3) -> Plane {
+        Plane::from_point_normal(a, &(*b - *a).cross(&(*c - *a)))
+    }
+
+    // Scales the plane equation so the normal is of unit length.
+    pub fn normalize(&self) -> Plane {
+        let length = self.normal.length();
+
+        Plane::new(self.normal / length, self.distance / length)
+    }
+
+    // Positive on the side the normal points to, negative on the other side.
+    pub fn signed_distance(&self, point: &Vector3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+// A ray starting at "origin" and going in "direction", which is always of unit length.
+// Because of this, the "t" returned from the intersection tests is the distance from the origin to the hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3
+}
+
+impl Ray {
+    pub fn new(origin: Vector3, direction: Vector3) -> Ray {
+        Ray {
+            origin,
+            direction: direction.normalize()
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Vector3 {
+        self.origin + self.direction * t
+    }
+
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denominator = plane.normal.dot(&self.direction);
+
+        if denominator.abs() < PARALLEL_EPSILON {
+            return None;
+        }
+
+        let t = -plane.signed_distance(&self.origin) / denominator;
+
+        if t >= 0.0 { Some(t) } else { None }
+    }
+
+    // Returns the distance to the first point where the ray enters the sphere, or zero if the origin is inside the sphere.
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        // Solving |origin + t * direction - center|^2 = radius^2 for t, with the direction being of unit length.
+        let to_origin = self.origin - sphere.center;
+        let b = to_origin.dot(&self.direction);
+        let c = to_origin.length_squared() - sphere.radius * sphere.radius;
+
+        // The origin is outside the sphere and the ray points away from it.
+        if c > 0.0 && b > 0.0 {
+            return None;
+        }
+
+        let discriminant = b * b - c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        Some((-b - discriminant.sqrt()).max(0.0))
+    }
+
+    // Slab test: the ray is clipped against the pair of planes of each axis, and hits if the intervals overlap.
+    // Returns the distance to the point where the ray enters the box, or zero if the origin is inside the box.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut t_min: f32 = 0.0;
+        let mut t_max = f32::INFINITY;
+
+        let origin = [self.origin.x, self.origin.y, self.origin.z];
+        let direction = [self.direction.x, self.direction.y, self.direction.z];
+        let min = [aabb.min.x, aabb.min.y, aabb.min.z];
+        let max = [aabb.max.x, aabb.max.y, aabb.max.z];
+
+        for axis in 0..3 {
+            if direction[axis].abs() < PARALLEL_EPSILON {
+                // Parallel to the slab, so the origin has to be between the planes.
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+
+                continue;
+            }
+
+            let inverse_direction = 1.0 / direction[axis];
+            let t0 = (min[axis] - origin[axis]) * inverse_direction;
+            let t1 = (max[axis] - origin[axis]) * inverse_direction;
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        Some(t_min)
+    }
+
+    // Möller-Trumbore ray-triangle intersection. Triangles are hit from both sides.
+    pub fn intersect_triangle(&self, a: &Vector3, b: &Vector3, c: &Vector3) -> Option<f32> {
+        let edge1 = *b - *a;
+        let edge2 = *c - *a;
+
+        let p = self.direction.cross(&edge2);
+        let determinant = edge1.dot(&p);
+
+        if determinant.abs() < PARALLEL_EPSILON {
+            return None;
+        }
+
+        let inverse_determinant = 1.0 / determinant;
+        let to_origin = self.origin - *a;
+
+        // u and v are the barycentric coordinates of the hit, which must be inside the triangle.
+        let u = to_origin.dot(&p) * inverse_determinant;
+
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = to_origin.cross(&edge1);
+        let v = self.direction.dot(&q) * inverse_determinant;
+
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q) * inverse_determinant;
+
+        if t >= 0.0 { Some(t) } else { None }
+    }
+}
+
+// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3
+}
+
+impl Aabb {
+    pub fn new(min: Vector3, max: Vector3) -> Aabb {
+        Aabb {
+            min,
+            max
+        }
+    }
+
+    pub fn from_center_half_extents(center: &Vector3, half_extents: &Vector3) -> Aabb {
+        Aabb::new(*center - *half_extents, *center + *half_extents)
+    }
+
+    // The smallest box containing all the points. Returns None if there are no points.
+    pub fn from_points(points: &[Vector3]) -> Option<Aabb> {
+        let first = points.first()?;
+
+        Some(points.iter().fold(Aabb::new(*first, *first), |aabb, point| Aabb::new(aabb.min.min(point), aabb.max.max(point))))
+    }
+
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vector3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x &&
+        point.y >= self.min.y && point.y <= self.max.y &&
+        point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.min.x <= aabb.max.x && self.max.x >= aabb.min.x &&
+        self.min.y <= aabb.max.y && self.max.y >= aabb.min.y &&
+        self.min.z <= aabb.max.z && self.max.z >= aabb.min.z
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sphere {
+    pub center: Vector3,
+    pub radius: f32
+}
+
+impl Sphere {
+    pub fn new(center: Vector3, radius: f32) -> Sphere {
+        Sphere {
+            center,
+            radius
+        }
+    }
+
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.center.distance(point) <= self.radius
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        let radius_sum = self.radius + sphere.radius;
+
+        (sphere.center - self.center).length_squared() <= radius_sum * radius_sum
+    }
+}
+
+// The six planes of a view frustum, with all normals pointing into the frustum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub left: Plane,
+    pub right: Plane,
+    pub bottom: Plane,
+    pub top: Plane,
+    pub near: Plane,
+    pub far: Plane
+}
+
+impl Frustum {
+    // Extracts the frustum planes from a view-projection matrix (Gribb-Hartmann method).
+    // Since my matrices are multiplied with row vectors, the clip space coordinates are the dot products of the point with the
+    // COLUMNS of the matrix. A point is inside when -w <= x <= w, -w <= y <= w and 0 <= z <= w, which gives a plane for each inequality.
+    // Planes extracted from a world-view-projection matrix are in object space, and from a view-projection matrix in world space.
+    pub fn from_view_projection(view_projection: &Mat4) -> Frustum {
+        Frustum::from_view_projection_with_depth_range(view_projection, DepthRange::Standard)
+    }
+
+    // Same as "from_view_projection", for projections made with the given depth range.
+    // With Reversed, z = 0 is the far plane and z = w the near plane, so the two planes swap places.
+    pub fn from_view_projection_with_depth_range(view_projection: &Mat4, depth_range: DepthRange) -> Frustum {
+        let m = &view_projection.matrix;
+
+        let column = |index: usize| (Vector3::new(m[index], m[4 + index], m[8 + index]), m[12 + index]);
+
+        let (x, x_w) = column(0);
+        let (y, y_w) = column(1);
+        let (z, z_w) = column(2);
+        let (w, w_w) = column(3);
+
+        let zero_depth = frustum_plane(z, z_w);
+        let full_depth = frustum_plane(w - z, w_w - z_w);
+
+        let (near, far) = match depth_range {
+            DepthRange::Standard => (zero_depth, full_depth),
+            DepthRange::Reversed => (full_depth, zero_depth)
+        };
+
+        Frustum {
+            left: frustum_plane(w + x, w_w + x_w),
+            right: frustum_plane(w - x, w_w - x_w),
+            bottom: frustum_plane(w + y, w_w + y_w),
+            top: frustum_plane(w - y, w_w - y_w),
+            near,
+            far
+        }
+    }
+
+    pub fn planes(&self) -> [Plane; 6] {
+        [self.left, self.right, self.bottom, self.top, self.near, self.far]
+    }
+
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.planes().iter().all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    // For each plane, only the corner of the box furthest along the plane normal needs to be tested.
+    // If that corner is behind any plane, the whole box is outside.
+    // The test is conservative: boxes close to the frustum corners can be reported as intersecting even though they are just outside.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes().iter().all(|plane| {
+            let furthest_corner = Vector3::new(
+                if plane.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if plane.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if plane.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z });
+
+            plane.signed_distance(&furthest_corner) >= 0.0
+        })
+    }
+}
+
+// With an infinite far plane, the far plane comes out as (0, 0, 0, near): every point is in front of it, but it has no normal
+// and can not be normalized. It is kept as a plane without a normal, which contains every point and box.
+fn frustum_plane(normal: Vector3, distance: f32) -> Plane {
+    if normal.length_squared() < PARALLEL_EPSILON * PARALLEL_EPSILON {
+        return Plane::new(Vector3::default(), 0.0);
+    }
+
+    Plane::new(normal, distance).normalize()
+}
+
+// The geometry types are displayed with their fields by name, and the precision is passed on to the vectors and scalars,
+// so {:.2} shows every number with two decimals.
+macro_rules! impl_display_for_fields {
+    ($type:ident { $first:ident $(, $field:ident)* }) => {
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, concat!(stringify!($first), ": "))?;
+                fmt::Display::fmt(&self.$first, f)?;
+                $(
+                    write!(f, concat!(", ", stringify!($field), ": "))?;
+                    fmt::Display::fmt(&self.$field, f)?;
+                )*
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_display_for_fields!(Plane { normal, distance });
+impl_display_for_fields!(Ray { origin, direction });
+impl_display_for_fields!(Aabb { min, max });
+impl_display_for_fields!(Sphere { center, radius });
+
+// Displays one plane per line.
+impl fmt::Display for Frustum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = ["left", "right", "bottom", "top", "near", "far"];
+
+        for (index, (name, plane)) in names.iter().zip(self.planes().iter()).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{}: ", name)?;
+            fmt::Display::fmt(plane, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    fn test_frustum() -> Frustum {
+        let view = Mat4::look_at_lh(&Vector3::new(0.0, 0.0, -10.0), &Vector3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0));
+        let projection = Mat4::projection(90.0f32.to_radians(), 800.0, 800.0, 1.0, 100.0);
+
+        Frustum::from_view_projection(&view.mul(&projection))
+    }
+
+    #[test]
+    fn should_return_signed_distance_when_using_plane() {
+        // Arrange
+        let ground = Plane::from_points(&Vector3::new(0.0, 2.0, 0.0), &Vector3::new(0.0, 2.0, 1.0), &Vector3::new(1.0, 2.0, 0.0));
+
+        // Act & Assert
+        assert!((ground.normal.y - 1.0).abs() < 1e-6);
+        assert!((ground.signed_distance(&Vector3::new(5.0, 7.0, -3.0)) - 5.0).abs() < 1e-6);
+        assert!((ground.signed_distance(&Vector3::new(0.0, 0.0, 0.0)) + 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_return_distance_to_hit_when_ray_intersects_shapes() {
+        // Arrange
+        let ray = Ray::new(Vector3::new(0.0, 10.0, 0.0), Vector3::new(0.0, -2.0, 0.0));
+
+        // Act
+        let plane_hit = ray.intersect_plane(&Plane::from_point_normal(&Vector3::default(), &Vector3::new(0.0, 1.0, 0.0)));
+        let sphere_hit = ray.intersect_sphere(&Sphere::new(Vector3::new(0.0, 3.0, 0.0), 2.0));
+        let aabb_hit = ray.intersect_aabb(&unit_box());
+        let triangle_hit = ray.intersect_triangle(&Vector3::new(-1.0, 4.0, -1.0), &Vector3::new(0.0, 4.0, 1.0), &Vector3::new(1.0, 4.0, -1.0));
+
+        // Assert
+        assert_eq!(plane_hit, Some(10.0));
+        assert_eq!(sphere_hit, Some(5.0));
+        assert_eq!(aabb_hit, Some(9.0));
+        assert_eq!(triangle_hit, Some(6.0));
+    }
+
+    #[test]
+    fn should_return_none_when_ray_misses_shapes() {
+        // Arrange
+        let ray = Ray::new(Vector3::new(5.0, 10.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+        let pointing_away = Ray::new(Vector3::new(0.0, 10.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+
+        // Act & Assert
+        assert!(ray.intersect_sphere(&Sphere::new(Vector3::default(), 2.0)).is_none());
+        assert!(ray.intersect_aabb(&unit_box()).is_none());
+        assert!(ray.intersect_triangle(&Vector3::new(-1.0, 0.0, -1.0), &Vector3::new(0.0, 0.0, 1.0), &Vector3::new(1.0, 0.0, -1.0)).is_none());
+        assert!(pointing_away.intersect_plane(&Plane::from_point_normal(&Vector3::default(), &Vector3::new(0.0, 1.0, 0.0))).is_none());
+        assert!(pointing_away.intersect_aabb(&unit_box()).is_none());
+    }
+
+    #[test]
+    fn should_return_zero_when_ray_starts_inside_shape() {
+        // Arrange
+        let ray = Ray::new(Vector3::new(0.5, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+
+        // Act & Assert
+        assert_eq!(ray.intersect_aabb(&unit_box()), Some(0.0));
+        assert_eq!(ray.intersect_sphere(&Sphere::new(Vector3::default(), 1.0)), Some(0.0));
+    }
+
+    #[test]
+    fn should_detect_overlap_between_spheres_and_boxes() {
+        // Arrange
+        let sphere = Sphere::new(Vector3::default(), 1.0);
+
+        // Act & Assert
+        assert!(sphere.intersects_sphere(&Sphere::new(Vector3::new(1.5, 0.0, 0.0), 0.5)));
+        assert!(!sphere.intersects_sphere(&Sphere::new(Vector3::new(1.6, 0.0, 0.0), 0.5)));
+        assert!(unit_box().intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(1.5, 1.5, 0.0), &Vector3::new(0.5, 0.5, 0.5))));
+        assert!(!unit_box().intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(3.0, 0.0, 0.0), &Vector3::new(0.5, 0.5, 0.5))));
+    }
+
+    #[test]
+    fn should_contain_points_inside_view_when_extracting_frustum() {
+        // Arrange
+        let frustum = test_frustum();
+
+        // Act & Assert
+        assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, 0.0)));
+        assert!(frustum.contains_point(&Vector3::new(8.0, -8.0, 0.0)));
+        assert!(!frustum.contains_point(&Vector3::new(12.0, 0.0, 0.0)));
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, -9.5)));
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, 95.0)));
+    }
+
+    #[test]
+    fn should_match_standard_frustum_when_extracting_from_reversed_depth_projection() {
+        // Arrange
+        let view = Mat4::look_at_lh(&Vector3::new(0.0, 0.0, -10.0), &Vector3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0));
+        let projection = Mat4::projection_with_depth_range(90.0f32.to_radians(), 800.0, 800.0, 1.0, 100.0, DepthRange::Reversed);
+
+        // Act
+        let frustum = Frustum::from_view_projection_with_depth_range(&view.mul(&projection), DepthRange::Reversed);
+
+        // Assert
+        assert!(frustum.approx_eq(&test_frustum()));
+        assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, 0.0)));
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, -9.5)));
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, 95.0)));
+    }
+
+    #[test]
+    fn should_not_cull_distant_points_when_extracting_from_infinite_projection() {
+        for depth_range in [DepthRange::Standard, DepthRange::Reversed].iter() {
+            // Arrange
+            let view = Mat4::look_at_lh(&Vector3::new(0.0, 0.0, -10.0), &Vector3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0));
+            let projection = Mat4::projection_infinite(90.0f32.to_radians(), 800.0, 800.0, 1.0, *depth_range);
+
+            // Act
+            let frustum = Frustum::from_view_projection_with_depth_range(&view.mul(&projection), *depth_range);
+
+            // Assert
+            assert!(frustum.near.approx_eq(&test_frustum().near));
+            assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, 0.0)));
+            assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, 1.0e6)));
+            assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, -9.5)));
+            assert!(!frustum.contains_point(&Vector3::new(12.0, 0.0, 0.0)));
+            assert!(frustum.intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(0.0, 0.0, 1.0e6), &Vector3::new(1.0, 1.0, 1.0))));
+            assert!(!frustum.intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(0.0, 0.0, -15.0), &Vector3::new(1.0, 1.0, 1.0))));
+        }
+    }
+
+    #[test]
+    fn should_cull_boxes_outside_frustum() {
+        // Arrange
+        let frustum = test_frustum();
+
+        // Act & Assert
+        assert!(frustum.intersects_aabb(&unit_box()));
+        assert!(frustum.intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(10.5, 0.0, 0.0), &Vector3::new(1.0, 1.0, 1.0))));
+        assert!(!frustum.intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(20.0, 0.0, 0.0), &Vector3::new(1.0, 1.0, 1.0))));
+        assert!(!frustum.intersects_aabb(&Aabb::from_center_half_extents(&Vector3::new(0.0, 0.0, -15.0), &Vector3::new(1.0, 1.0, 1.0))));
+    }
+
+    #[test]
+    fn should_pass_precision_on_to_fields_when_using_display_formatter() {
+        // Arrange
+        let plane = Plane::new(Vector3::new(0.0, 1.0, 0.0), -2.0);
+        let sphere = Sphere::new(Vector3::new(1.0, 2.0, 3.0), 0.25);
+
+        // Act
+        let plane_output = format!("{:.1}", plane);
+        let sphere_output = format!("{}", sphere);
+        let frustum_output = format!("{:.2}", test_frustum());
+
+        // Assert
+        assert_eq!(plane_output, "normal: (0.0, 1.0, 0.0), distance: -2.0");
+        assert_eq!(sphere_output, "center: (1, 2, 3), radius: 0.25");
+        assert_eq!(frustum_output.lines().count(), 6);
+        assert!(frustum_output.starts_with("left: normal: (0.71, 0.00, 0.71), distance: "));
+    }
+
+    #[test]
+    fn should_return_smallest_enclosing_box_when_creating_aabb_from_points() {
+        // Arrange
+        let points = [Vector3::new(1.0, -2.0, 3.0), Vector3::new(-1.0, 4.0, 0.0), Vector3::new(0.0, 0.0, -5.0)];
+
+        // Act
+        let aabb = Aabb::from_points(&points).expect("There should be a box for non-empty points.");
+
+        // Assert
+        assert_eq!((aabb.min.x, aabb.min.y, aabb.min.z), (-1.0, -2.0, -5.0));
+        assert_eq!((aabb.max.x, aabb.max.y, aabb.max.z), (1.0, 4.0, 3.0));
+        assert!(Aabb::from_points(&[]).is_none());
+    }
+}
@@ -0,0 +1,594 @@
+use crate::beagle_math::Vector2;
+
+// Below this value, two segments are treated as parallel.
+const PARALLEL_EPSILON: f32 = 1e-6;
+
+// A straight line between two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment2 {
+    pub start: Vector2,
+    pub end: Vector2
+}
+
+impl Segment2 {
+    pub fn new(start: Vector2, end: Vector2) -> Segment2 {
+        Segment2 {
+            start,
+            end
+        }
+    }
+
+    pub fn direction(&self) -> Vector2 {
+        self.end - self.start
+    }
+
+    pub fn length(&self) -> f32 {
+        self.direction().length()
+    }
+
+    // The unit length normal on the left side of the segment, when looking from start to end.
+    // For terrain drawn from left to right, this is the normal pointing up out of the ground.
+    pub fn normal(&self) -> Vector2 {
+        self.direction().perp().normalize()
+    }
+
+    pub fn closest_point(&self, point: &Vector2) -> Vector2 {
+        let direction = self.direction();
+        let length_squared = direction.length_squared();
+
+        // A segment of zero length is a single point.
+        if length_squared == 0.0 {
+            return self.start;
+        }
+
+        let t = ((*point - self.start).dot(&direction) / length_squared).clamp(0.0, 1.0);
+
+        self.start + direction * t
+    }
+
+    pub fn distance_to_point(&self, point: &Vector2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    // The point where the two segments cross, including touching at the end points.
+    // Parallel segments, including overlapping ones on the same line, give None.
+    pub fn intersect(&self, segment: &Segment2) -> Option<Vector2> {
+        let (t, _) = self.intersection_parameters(segment)?;
+
+        Some(self.start + self.direction() * t)
+    }
+
+    // How far along each segment, in [0, 1], the intersection is.
+    fn intersection_parameters(&self, segment: &Segment2) -> Option<(f32, f32)> {
+        let direction = self.direction();
+        let other_direction = segment.direction();
+        let denominator = direction.cross(&other_direction);
+
+        if denominator.abs() < PARALLEL_EPSILON {
+            return None;
+        }
+
+        let offset = segment.start - self.start;
+        let t = offset.cross(&other_direction) / denominator;
+        let u = offset.cross(&direction) / denominator;
+
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            Some((t, u))
+        } else {
+            None
+        }
+    }
+}
+
+// A closed polygon, with an edge from the last point back to the first.
+// Counter-clockwise polygons have a positive area, clockwise ones a negative area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon2 {
+    pub points: Vec<Vector2>
+}
+
+impl Polygon2 {
+    // Returns None if there are fewer than three points.
+    pub fn new(points: Vec<Vector2>) -> Option<Polygon2> {
+        if points.len() < 3 {
+            return None;
+        }
+
+        Some(Polygon2 {
+            points
+        })
+    }
+
+    // The smallest convex polygon containing all the points, in counter-clockwise order, using Andrew's monotone chain algorithm.
+    // Points on the edges of the hull are left out. Returns None if the points are all on one line.
+    pub fn convex_hull(points: &[Vector2]) -> Option<Polygon2> {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        sorted.dedup();
+
+        // A point is only kept while the chain turns counter-clockwise at it.
+        let build_chain = |points: &mut dyn Iterator<Item = &Vector2>| {
+            let mut chain: Vec<Vector2> = Vec::new();
+
+            for point in points {
+                while chain.len() >= 2 && (chain[chain.len() - 1] - chain[chain.len() - 2]).cross(&(*point - chain[chain.len() - 2])) <= 0.0 {
+                    chain.pop();
+                }
+
+                chain.push(*point);
+            }
+
+            // The last point is the first point of the other chain.
+            chain.pop();
+            chain
+        };
+
+        let mut hull = build_chain(&mut sorted.iter());
+        hull.extend(build_chain(&mut sorted.iter().rev()));
+
+        Polygon2::new(hull)
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Segment2> + '_ {
+        let count = self.points.len();
+
+        (0..count).map(move |i| Segment2::new(self.points[i], self.points[(i + 1) % count]))
+    }
+
+    // The signed area, using the shoelace formula.
+    pub fn signed_area(&self) -> f32 {
+        self.edges().map(|edge| edge.start.cross(&edge.end)).sum::<f32>() / 2.0
+    }
+
+    pub fn is_convex(&self) -> bool {
+        let count = self.points.len();
+        let mut sign = 0.0;
+
+        for i in 0..count {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % count];
+            let c = self.points[(i + 2) % count];
+            let turn = (b - a).cross(&(c - b));
+
+            if turn != 0.0 {
+                if sign != 0.0 && turn.signum() != sign {
+                    return false;
+                }
+
+                sign = turn.signum();
+            }
+        }
+
+        true
+    }
+
+    // Casts a ray from the point towards +X and counts the edges it crosses: an odd count means the point is inside.
+    // Works for concave polygons too. Points exactly on an edge may be counted as either inside or outside.
+    pub fn contains_point(&self, point: &Vector2) -> bool {
+        let mut inside = false;
+
+        for edge in self.edges() {
+            let (a, b) = (edge.start, edge.end);
+
+            if (a.y > point.y) != (b.y > point.y) {
+                let crossing_x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+
+                if point.x < crossing_x {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+
+    // Splits the polygon into triangles, returned as indices into "points". Every triangle keeps the winding order
+    // of the polygon, so front faces stay front faces. A polygon with n points always gives n - 2 triangles.
+    // Convex polygons are split as a fan around the first point. Concave polygons use ear clipping: a corner is an "ear"
+    // when it turns the same way as the polygon and no other point lies in the triangle it forms with its neighbours,
+    // and every ear can be cut off without the triangle leaving the polygon.
+    // Self-intersecting polygons have no correct triangulation; they still give n - 2 triangles, but some may overlap.
+    pub fn triangulate(&self) -> Vec<[usize; 3]> {
+        let count = self.points.len();
+
+        // "points" is public, so it can have been emptied after the polygon was created.
+        if count < 3 {
+            return Vec::new();
+        }
+
+        if self.is_convex() {
+            return (1..count - 1).map(|i| [0, i, i + 1]).collect();
+        }
+
+        let orientation = self.signed_area().signum();
+        let turn = |a: usize, b: usize, c: usize| (self.points[b] - self.points[a]).cross(&(self.points[c] - self.points[b])) * orientation;
+
+        let mut remaining: Vec<usize> = (0..count).collect();
+        let mut triangles = Vec::with_capacity(count - 2);
+
+        while remaining.len() > 3 {
+            let length = remaining.len();
+            let corner = |i: usize| (remaining[(i + length - 1) % length], remaining[i], remaining[(i + 1) % length]);
+
+            let is_ear = |i: usize| {
+                let (previous, current, next) = corner(i);
+
+                turn(previous, current, next) > 0.0 && remaining.iter().all(|&other| {
+                    other == previous || other == current || other == next
+                        || !triangle_contains_point(&self.points[previous], &self.points[current], &self.points[next], &self.points[other])
+                })
+            };
+
+            // Without an ear, the polygon intersects itself or is degenerate, and any corner is cut off to keep going.
+            let ear = (0..length).find(|&i| is_ear(i)).unwrap_or(0);
+            let (previous, current, next) = corner(ear);
+
+            triangles.push([previous, current, next]);
+            remaining.remove(ear);
+        }
+
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+        triangles
+    }
+}
+
+// Points on the edges count as inside, so an ear is never cut off through a point touching it.
+fn triangle_contains_point(a: &Vector2, b: &Vector2, c: &Vector2, point: &Vector2) -> bool {
+    let ab = (*b - *a).cross(&(*point - *a));
+    let bc = (*c - *b).cross(&(*point - *b));
+    let ca = (*a - *c).cross(&(*point - *c));
+
+    (ab >= 0.0 && bc >= 0.0 && ca >= 0.0) || (ab <= 0.0 && bc <= 0.0 && ca <= 0.0)
+}
+
+// Where a moving circle first touched a polyline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    // How far along the movement the hit happened, from 0.0 at the start to 1.0 at the end.
+    pub time: f32,
+    // The center of the circle when it touched.
+    pub position: Vector2,
+    // The point on the polyline that was touched.
+    pub point: Vector2,
+    // The unit length normal of the surface at the touched point, pointing towards the circle.
+    pub normal: Vector2
+}
+
+// An open chain of segments, such as the terrain line of the classic 2D lander.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline2 {
+    pub points: Vec<Vector2>
+}
+
+impl Polyline2 {
+    // Returns None if there are fewer than two points.
+    pub fn new(points: Vec<Vector2>) -> Option<Polyline2> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        Some(Polyline2 {
+            points
+        })
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = Segment2> + '_ {
+        self.points.windows(2).map(|pair| Segment2::new(pair[0], pair[1]))
+    }
+
+    pub fn length(&self) -> f32 {
+        self.segments().map(|segment| segment.length()).sum()
+    }
+
+    pub fn closest_point(&self, point: &Vector2) -> Vector2 {
+        self.segments()
+            .map(|segment| segment.closest_point(point))
+            .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+            .unwrap()
+    }
+
+    pub fn distance_to_point(&self, point: &Vector2) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    // The first point where the segment crosses the polyline, when going from its start to its end.
+    pub fn intersect_segment(&self, segment: &Segment2) -> Option<Vector2> {
+        self.segments()
+            .filter_map(|edge| segment.intersection_parameters(&edge))
+            .map(|(t, _)| t)
+            .min_by(|a, b| a.total_cmp(b))
+            .map(|t| segment.start + segment.direction() * t)
+    }
+
+    // Moves a circle from "start" to "end", and finds the first point where it touches the polyline.
+    // Unlike only testing the end position, this cannot miss the terrain when the lander moves further than its radius in one frame.
+    // If the circle already overlaps the polyline at the start, the hit is at time 0.0.
+    pub fn sweep_circle(&self, start: &Vector2, end: &Vector2, radius: f32) -> Option<SweepHit> {
+        let motion = *end - *start;
+
+        self.segments()
+            .filter_map(|segment| sweep_circle_segment(&segment, start, &motion, radius))
+            .min_by(|a, b| a.time.total_cmp(&b.time))
+    }
+}
+
+fn sweep_circle_segment(segment: &Segment2, start: &Vector2, motion: &Vector2, radius: f32) -> Option<SweepHit> {
+    let hit = |time: f32, point: Vector2, fallback_normal: Vector2| {
+        let position = *start + *motion * time;
+        let offset = position - point;
+        let normal = if offset.length_squared() > 0.0 { offset.normalize() } else { fallback_normal };
+
+        SweepHit {
+            time,
+            position,
+            point,
+            normal
+        }
+    };
+
+    let closest = segment.closest_point(start);
+
+    if closest.distance(start) <= radius {
+        return Some(hit(0.0, closest, segment.normal()));
+    }
+
+    let mut first: Option<SweepHit> = None;
+
+    // The circle touching the inside of the segment, which happens when its center is "radius" away from the line.
+    let direction = segment.direction();
+
+    if direction.length_squared() > 0.0 {
+        let mut normal = direction.perp().normalize();
+        let mut distance = (*start - segment.start).dot(&normal);
+
+        if distance < 0.0 {
+            normal = -normal;
+            distance = -distance;
+        }
+
+        let approach = motion.dot(&normal);
+
+        if approach < 0.0 {
+            let time = (radius - distance) / approach;
+
+            // A negative time means the circle was touching the line before the start, and is now moving away from it.
+            if (0.0..=1.0).contains(&time) {
+                let position = *start + *motion * time;
+                let along = (position - segment.start).dot(&direction) / direction.length_squared();
+
+                if (0.0..=1.0).contains(&along) {
+                    first = Some(hit(time, position - normal * radius, normal));
+                }
+            }
+        }
+    }
+
+    // The circle touching one of the end points, which is a ray from the start hitting a circle of "radius" around the end point.
+    for corner in [segment.start, segment.end].iter() {
+        let offset = *start - *corner;
+        let a = motion.length_squared();
+        let b = offset.dot(motion);
+        let c = offset.length_squared() - radius * radius;
+        let discriminant = b * b - a * c;
+
+        if a == 0.0 || discriminant < 0.0 {
+            continue;
+        }
+
+        let time = (-b - discriminant.sqrt()) / a;
+
+        let earlier = match first {
+            Some(first) => time < first.time,
+            None => true
+        };
+
+        if (0.0..=1.0).contains(&time) && earlier {
+            first = Some(hit(time, *corner, segment.normal()));
+        }
+    }
+
+    first
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    fn terrain() -> Polyline2 {
+        Polyline2::new(vec![
+            Vector2::new(0.0, 10.0),
+            Vector2::new(10.0, 2.0),
+            Vector2::new(20.0, 2.0),
+            Vector2::new(30.0, 12.0)
+        ]).unwrap()
+    }
+
+    #[test]
+    fn should_find_crossing_when_intersecting_segments() {
+        // Arrange
+        let a = Segment2::new(Vector2::new(0.0, 0.0), Vector2::new(4.0, 4.0));
+        let b = Segment2::new(Vector2::new(0.0, 4.0), Vector2::new(4.0, 0.0));
+        let c = Segment2::new(Vector2::new(5.0, 0.0), Vector2::new(9.0, 4.0));
+
+        // Assert
+        assert!(a.intersect(&b).unwrap().approx_eq(&Vector2::new(2.0, 2.0)));
+        assert!(a.intersect(&c).is_none());
+        assert!(b.intersect(&c).is_none());
+    }
+
+    #[test]
+    fn should_clamp_to_end_points_when_measuring_distance_to_segment() {
+        // Arrange
+        let segment = Segment2::new(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0));
+
+        // Assert
+        assert_eq!(segment.distance_to_point(&Vector2::new(5.0, 3.0)), 3.0);
+        assert_eq!(segment.distance_to_point(&Vector2::new(13.0, 4.0)), 5.0);
+        assert_eq!(segment.normal(), Vector2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn should_leave_out_inner_points_when_building_convex_hull() {
+        // Arrange
+        let points = [
+            Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0), Vector2::new(2.0, 1.0), Vector2::new(4.0, 4.0),
+            Vector2::new(2.0, 0.0), Vector2::new(0.0, 4.0), Vector2::new(1.0, 3.0), Vector2::new(4.0, 4.0)
+        ];
+
+        // Act
+        let hull = Polygon2::convex_hull(&points).unwrap();
+
+        // Assert
+        assert_eq!(hull.points, vec![Vector2::new(0.0, 0.0), Vector2::new(4.0, 0.0), Vector2::new(4.0, 4.0), Vector2::new(0.0, 4.0)]);
+        assert_eq!(hull.signed_area(), 16.0);
+        assert!(hull.is_convex());
+        assert!(Polygon2::convex_hull(&[Vector2::new(0.0, 0.0), Vector2::new(1.0, 1.0), Vector2::new(2.0, 2.0)]).is_none());
+    }
+
+    #[test]
+    fn should_handle_concave_polygons_when_testing_point_in_polygon() {
+        // Arrange
+        let crater = Polygon2::new(vec![
+            Vector2::new(0.0, 0.0), Vector2::new(6.0, 0.0), Vector2::new(6.0, 6.0),
+            Vector2::new(3.0, 2.0), Vector2::new(0.0, 6.0)
+        ]).unwrap();
+
+        // Assert
+        assert!(crater.contains_point(&Vector2::new(1.0, 1.0)));
+        assert!(crater.contains_point(&Vector2::new(5.0, 4.0)));
+        assert!(!crater.contains_point(&Vector2::new(3.0, 4.0)));
+        assert!(!crater.contains_point(&Vector2::new(-1.0, 1.0)));
+        assert!(!crater.is_convex());
+    }
+
+    fn triangle_area(polygon: &Polygon2, triangle: &[usize; 3]) -> f32 {
+        let [a, b, c] = triangle.map(|i| polygon.points[i]);
+        (b - a).cross(&(c - a)) / 2.0
+    }
+
+    #[test]
+    fn should_fan_from_first_point_when_triangulating_convex_polygon() {
+        // Arrange
+        let hexagon = Polygon2::new((0..6).map(|i| Vector2::new(1.0, 0.0).rotate(i as f32 * std::f32::consts::PI / 3.0)).collect()).unwrap();
+
+        // Act
+        let triangles = hexagon.triangulate();
+
+        // Assert
+        assert_eq!(triangles, vec![[0, 1, 2], [0, 2, 3], [0, 3, 4], [0, 4, 5]]);
+    }
+
+    #[test]
+    fn should_return_no_triangles_when_polygon_has_too_few_points() {
+        // Arrange
+        let mut polygon = Polygon2::new(vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(0.0, 1.0)]).unwrap();
+
+        // Act
+        polygon.points.truncate(2);
+
+        // Assert
+        assert!(polygon.triangulate().is_empty());
+    }
+
+    #[test]
+    fn should_cover_polygon_with_same_winding_when_triangulating_concave_polygon() {
+        // Arrange
+        let crater = Polygon2::new(vec![
+            Vector2::new(0.0, 0.0), Vector2::new(6.0, 0.0), Vector2::new(6.0, 6.0),
+            Vector2::new(3.0, 2.0), Vector2::new(0.0, 6.0)
+        ]).unwrap();
+        let clockwise = Polygon2::new(crater.points.iter().rev().copied().collect()).unwrap();
+
+        for polygon in [crater, clockwise].iter() {
+            // Act
+            let triangles = polygon.triangulate();
+
+            // Assert
+            let areas: Vec<f32> = triangles.iter().map(|triangle| triangle_area(polygon, triangle)).collect();
+            assert_eq!(triangles.len(), 3);
+            assert!(areas.iter().all(|area| area.signum() == polygon.signed_area().signum()));
+            assert!(areas.iter().sum::<f32>().approx_eq(&polygon.signed_area()));
+
+            // No triangle may cover the notch of the crater, which is outside the polygon.
+            let notch = Vector2::new(3.0, 4.0);
+            assert!(triangles.iter().all(|[a, b, c]| {
+                let (a, b, c) = (polygon.points[*a], polygon.points[*b], polygon.points[*c]);
+                let signs = [(b - a).cross(&(notch - a)), (c - b).cross(&(notch - b)), (a - c).cross(&(notch - c))];
+                !(signs.iter().all(|s| *s > 0.0) || signs.iter().all(|s| *s < 0.0))
+            }));
+        }
+    }
+
+    #[test]
+    fn should_hit_flat_landing_zone_when_sweeping_circle_down() {
+        // Arrange
+        let terrain = terrain();
+
+        // Act
+        let hit = terrain.sweep_circle(&Vector2::new(15.0, 10.0), &Vector2::new(15.0, -10.0), 1.0).unwrap();
+
+        // Assert
+        assert!(hit.time.approx_eq(&(7.0 / 20.0)));
+        assert!(hit.position.approx_eq(&Vector2::new(15.0, 3.0)));
+        assert!(hit.point.approx_eq(&Vector2::new(15.0, 2.0)));
+        assert!(hit.normal.approx_eq(&Vector2::new(0.0, 1.0)));
+    }
+
+    #[test]
+    fn should_not_tunnel_through_terrain_when_moving_fast() {
+        // Arrange
+        let terrain = terrain();
+        let start = Vector2::new(12.0, 20.0);
+        let end = Vector2::new(12.0, -50.0);
+
+        // Act
+        let hit = terrain.sweep_circle(&start, &end, 0.5);
+
+        // Assert
+        assert!(terrain.distance_to_point(&end) > 0.5);
+        assert!(hit.unwrap().point.approx_eq(&Vector2::new(12.0, 2.0)));
+        assert!(terrain.intersect_segment(&Segment2::new(start, end)).unwrap().approx_eq(&Vector2::new(12.0, 2.0)));
+    }
+
+    #[test]
+    fn should_hit_corner_when_sweeping_past_end_point() {
+        // Arrange
+        let ledge = Polyline2::new(vec![Vector2::new(-10.0, 0.0), Vector2::new(0.0, 0.0)]).unwrap();
+
+        // Act
+        let hit = ledge.sweep_circle(&Vector2::new(0.6, 5.0), &Vector2::new(0.6, -5.0), 1.0).unwrap();
+
+        // Assert
+        assert!(hit.point.approx_eq(&Vector2::new(0.0, 0.0)));
+        assert!(hit.position.approx_eq(&Vector2::new(0.6, 0.8)));
+        assert!(hit.normal.approx_eq(&Vector2::new(0.6, 0.8)));
+        assert!(ledge.sweep_circle(&Vector2::new(2.0, 5.0), &Vector2::new(2.0, -5.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn should_not_hit_when_moving_away_from_ledge() {
+        // Arrange
+        // The circle is within its radius of the line through the ledge, but past its end, and moving further away.
+        let ledge = Polyline2::new(vec![Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0)]).unwrap();
+
+        // Act
+        let hit = ledge.sweep_circle(&Vector2::new(11.0, 0.5), &Vector2::new(13.0, -0.5), 1.0);
+
+        // Assert
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn should_not_panic_when_points_are_nan() {
+        // Arrange
+        let points = [Vector2::new(0.0, 0.0), Vector2::new(f32::NAN, 1.0), Vector2::new(4.0, 0.0), Vector2::new(0.0, 4.0)];
+        let terrain = terrain();
+        let nan = Vector2::new(f32::NAN, 0.0);
+
+        // Act & Assert
+        Polygon2::convex_hull(&points);
+        terrain.closest_point(&nan);
+        terrain.intersect_segment(&Segment2::new(nan, Vector2::new(12.0, -50.0)));
+        terrain.sweep_circle(&nan, &Vector2::new(12.0, -50.0), 0.5);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::beagle_math::{Mat3, Mat4};
+
+// My matrices are all designed for being multiplied with a ROW vector, and are stored in row-major order in memory.
+// By default, HLSL will both READ and PACK matrices in column-major order, so a Mat4 written directly into a constant buffer
+// would be read transposed on the shader side.
+// GpuMat4 is the only matrix type that should be placed in a constant buffer. It can only be created through Mat4::to_gpu,
+// which does the conversion, so forgetting it becomes a compile error instead of a wrong picture.
+// With the matrix read as intended, the shader multiplies row vectors just like the CPU side: mul(float4(pos, 1.0f), matrix).
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuMat4 {
+    column_major: [f32; 16]
+}
+
+// The GPU layout of a float3x3 in a constant buffer.
+// HLSL places every column in its own 16 byte register, so each column is followed by a padding element.
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuMat3 {
+    padded_column_major: [f32; 12]
+}
+
+impl Mat4 {
+    pub fn to_gpu(&self) -> GpuMat4 {
+        GpuMat4 {
+            column_major: self.get_column_major_value()
+        }
+    }
+}
+
+impl Mat3 {
+    pub fn to_gpu(&self) -> GpuMat3 {
+        GpuMat3 {
+            padded_column_major: self.get_padded_column_major_value()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use crate::beagle_math::*;
+
+    // Reads back the bytes of a value as the f32s the GPU would see.
+    fn read_floats<T>(value: &T) -> Vec<f32> {
+        let bytes = unsafe { std::slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) };
+
+        bytes.chunks(4).map(|chunk| f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])).collect()
+    }
+
+    #[test]
+    fn should_write_columns_consecutively_when_converting_mat4_for_gpu() {
+        // Arrange
+        let matrix = Mat4::new([
+            11.0, 12.0, 13.0, 14.0,
+            21.0, 22.0, 23.0, 24.0,
+            31.0, 32.0, 33.0, 34.0,
+            41.0, 42.0, 43.0, 44.0
+        ]);
+
+        // Act
+        let gpu_matrix = matrix.to_gpu();
+
+        // Assert
+        assert_eq!(mem::size_of::<GpuMat4>(), 64);
+        assert_eq!(mem::align_of::<GpuMat4>(), 16);
+        assert_eq!(read_floats(&gpu_matrix), vec![
+            11.0, 21.0, 31.0, 41.0,
+            12.0, 22.0, 32.0, 42.0,
+            13.0, 23.0, 33.0, 43.0,
+            14.0, 24.0, 34.0, 44.0
+        ]);
+    }
+
+    #[test]
+    fn should_place_translation_in_last_register_when_converting_mat4_for_gpu() {
+        // Arrange
+        let matrix = Mat4::translate(&Vector3::new(5.0, 6.0, 7.0));
+
+        // Act
+        let floats = read_floats(&matrix.to_gpu());
+
+        // Assert
+        // HLSL reads the registers as columns, so the fourth element of the first three registers is the translation.
+        assert_eq!((floats[3], floats[7], floats[11]), (5.0, 6.0, 7.0));
+    }
+
+    #[test]
+    fn should_pad_every_register_when_converting_mat3_for_gpu() {
+        // Arrange
+        let matrix = Mat3::new([
+            11.0, 12.0, 13.0,
+            21.0, 22.0, 23.0,
+            31.0, 32.0, 33.0
+        ]);
+
+        // Act
+        let gpu_matrix = matrix.to_gpu();
+
+        // Assert
+        assert_eq!(mem::size_of::<GpuMat3>(), 48);
+        assert_eq!(read_floats(&gpu_matrix), vec![
+            11.0, 21.0, 31.0, 0.0,
+            12.0, 22.0, 32.0, 0.0,
+            13.0, 23.0, 33.0, 0.0
+        ]);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::beagle_math::{Mat4, Quaternion, Vector3};
+
+// A stack of matrices for drawing hierarchies, like the classic OpenGL matrix stack, so the current transform does not have
+// to be passed through every debug drawing call.
+// The top of the stack is the current transform. Pushing saves it, and popping returns to the saved transform.
+// Multiplying applies a matrix BEFORE the current transform (m * top), since with row vectors that places the matrix
+// in the space of the current transform, just like a child in the scene graph.
+// The stack is never empty: the bottom matrix can be replaced, but not popped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixStack {
+    stack: Vec<Mat4>
+}
+
+impl Default for MatrixStack {
+    fn default() -> MatrixStack {
+        MatrixStack::new()
+    }
+}
+
+impl MatrixStack {
+    // A stack holding only the identity matrix.
+    pub fn new() -> MatrixStack {
+        MatrixStack::with_base(Mat4::identity())
+    }
+
+    // A stack starting at the given matrix, such as the view-projection matrix.
+    pub fn with_base(base: Mat4) -> MatrixStack {
+        MatrixStack {
+            stack: vec![base]
+        }
+    }
+
+    pub fn top(&self) -> Mat4 {
+        *self.stack.last().unwrap()
+    }
+
+    // The number of matrices on the stack, which is 1 when nothing has been pushed.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    // Saves the current transform by pushing a copy of it.
+    pub fn push(&mut self) {
+        self.stack.push(self.top());
+    }
+
+    // Returns to the transform saved by the matching push, and returns the transform that was removed.
+    // Returns None, and leaves the stack untouched, if there is no matching push.
+    pub fn pop(&mut self) -> Option<Mat4> {
+        if self.stack.len() == 1 {
+            return None;
+        }
+
+        self.stack.pop()
+    }
+
+    // Replaces the current transform.
+    pub fn load(&mut self, mat: &Mat4) {
+        *self.stack.last_mut().unwrap() = *mat;
+    }
+
+    pub fn multiply(&mut self, mat: &Mat4) {
+        let top = mat.mul(&self.top());
+        self.load(&top);
+    }
+
+    pub fn translate(&mut self, translation: &Vector3) {
+        self.multiply(&Mat4::translate(translation));
+    }
+
+    pub fn rotate(&mut self, rotation: &Quaternion) {
+        self.multiply(&rotation.to_mat4());
+    }
+
+    pub fn scale(&mut self, scale: &Vector3) {
+        self.multiply(&Mat4::scale(scale));
+    }
+
+    // Pushes, runs "draw" and pops again, so the push and pop can never get out of balance.
+    pub fn scoped<R, F: FnOnce(&mut MatrixStack) -> R>(&mut self, draw: F) -> R {
+        let depth = self.depth();
+        self.push();
+
+        let result = draw(self);
+
+        self.stack.truncate(depth);
+        result
+    }
+
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        self.top().transform_point(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    #[test]
+    fn should_restore_previous_transform_when_popping() {
+        // Arrange
+        let mut stack = MatrixStack::new();
+        stack.translate(&Vector3::new(0.0, 10.0, 0.0));
+        let body = stack.top();
+
+        // Act
+        stack.push();
+        stack.translate(&Vector3::new(1.0, 0.0, 0.0));
+        let leg = stack.transform_point(&Vector3::new(0.0, 0.0, 0.0));
+        let popped = stack.pop();
+
+        // Assert
+        assert!(leg.approx_eq(&Vector3::new(1.0, 10.0, 0.0)));
+        assert_eq!(popped, Some(Mat4::translate(&Vector3::new(1.0, 0.0, 0.0)).mul(&body)));
+        assert_eq!(stack.top(), body);
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.depth(), 1);
+    }
+
+    #[test]
+    fn should_place_child_in_space_of_parent_when_multiplying() {
+        // Arrange
+        let mut stack = MatrixStack::new();
+
+        // Act
+        stack.rotate(&Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2));
+        stack.translate(&Vector3::new(0.0, 0.0, 2.0));
+
+        // Assert
+        // The translation is along the rotated Z axis, which the rotation about Y has turned into the X axis.
+        assert!(stack.transform_point(&Vector3::new(0.0, 0.0, 0.0)).approx_eq(&Vector3::new(2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn should_keep_balance_when_using_scoped() {
+        // Arrange
+        let mut stack = MatrixStack::with_base(Mat4::scale(&Vector3::new(2.0, 2.0, 2.0)));
+
+        // Act
+        let inner = stack.scoped(|stack| {
+            stack.translate(&Vector3::new(1.0, 0.0, 0.0));
+            stack.push();
+            stack.push();
+            stack.transform_point(&Vector3::new(0.0, 0.0, 0.0))
+        });
+
+        // Assert
+        assert!(inner.approx_eq(&Vector3::new(2.0, 0.0, 0.0)));
+        assert_eq!(stack.depth(), 1);
+        assert_eq!(stack.top(), Mat4::scale(&Vector3::new(2.0, 2.0, 2.0)));
+    }
+}
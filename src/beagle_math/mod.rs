@@ -4,16 +4,117 @@ use std::fmt::{self};
 // Need the trait "Copy"
 use std::marker::{Copy};
 
-use num_traits::Pow;
+// The "ops" module contains the traits used for operator overloading, such as "+" (Add) and "*" (Mul).
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num_traits::Float;
+
+// Sub modules of beagle_math, with their types re-exported so everything can be used directly from beagle_math.
+mod transform;
+mod geometry;
+mod gpu;
+mod simd;
+mod approx;
+mod euler;
+mod curve;
+mod tween;
+mod random;
+mod noise;
+mod geometry2d;
+mod color;
+mod dual_quaternion;
+mod matrix_stack;
+
+// Not re-exported, as its functions have the same names as the methods of f32 and f64.
+pub mod deterministic;
 
-#[derive(Default, Clone, Copy)]
-pub struct Vector2
+#[cfg(test)]
+mod properties;
+
+pub use transform::Transform;
+pub use geometry::{Aabb, Frustum, Plane, Ray, Sphere};
+pub use gpu::{GpuMat3, GpuMat4};
+pub use approx::ApproxEq;
+pub use euler::{EulerAngles, EulerOrder};
+pub use curve::Spline;
+pub use tween::{Easing, EasingCurve, Lerp, Tween};
+pub use random::Random;
+pub use noise::{Fractal, Noise, NoiseBasis, PermutationTable};
+pub use geometry2d::{Polygon2, Polyline2, Segment2, SweepHit};
+pub use color::{linear_to_srgb, srgb_to_linear, BlendMode, Color};
+pub use deterministic::math;
+pub use dual_quaternion::DualQuaternion;
+pub use matrix_stack::MatrixStack;
+
+use simd::MatrixKernels;
+
+// The floating point types the vectors, matrices and quaternions can be built on.
+// f32 is what the GPU works with, while f64 gives the precision needed when simulating a descent from orbit,
+// where distances of kilometres and movements of millimetres have to be represented at the same time.
+pub trait Real: Float + Default + fmt::Debug + fmt::Display + AddAssign + SubAssign + MulAssign + DivAssign + ApproxEq<Epsilon = Self> + MatrixKernels {
+    // Converts constants, such as 0.5 or 2.0, and values of other precisions into this type.
+    fn from_f64(value: f64) -> Self;
+
+    fn into_f64(self) -> f64;
+}
+
+impl Real for f32 {
+    fn from_f64(value: f64) -> f32 {
+        value as f32
+    }
+
+    fn into_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Real for f64 {
+    fn from_f64(value: f64) -> f64 {
+        value
+    }
+
+    fn into_f64(self) -> f64 {
+        self
+    }
+}
+
+// The math types are generic over their scalar type. The aliases below are the names used throughout the game,
+// with the single precision (f32) versions keeping the plain names and the double precision (f64) versions prefixed with D.
+pub type Vector2 = Vec2<f32>;
+pub type Vector3 = Vec3<f32>;
+pub type Vector4 = Vec4<f32>;
+pub type Mat3 = Matrix3<f32>;
+pub type Mat4 = Matrix4<f32>;
+pub type Quaternion = Quat<f32>;
+
+pub type DVector2 = Vec2<f64>;
+pub type DVector3 = Vec3<f64>;
+pub type DVector4 = Vec4<f64>;
+pub type DMat3 = Matrix3<f64>;
+pub type DMat4 = Matrix4<f64>;
+pub type DQuaternion = Quat<f64>;
+
+// Writes the values separated by commas, passing the formatting options (such as the precision in "{:.2}") on to every value.
+fn write_separated<T: fmt::Display>(f: &mut fmt::Formatter<'_>, values: &[T]) -> fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+
+        fmt::Display::fmt(value, f)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Vec2<T>
 {
-    pub x: f32,
-    pub y: f32
+    pub x: T,
+    pub y: T
 }
 
-impl fmt::Debug for Vector2 {
+impl<T: Real> fmt::Debug for Vec2<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Vector2")
         .field("x", &self.x)
@@ -22,24 +123,56 @@ impl fmt::Debug for Vector2 {
     }
 }
 
-impl Vector2 {
-    pub fn new(x: f32, y: f32) -> Vector2 {
-        Vector2 {
+impl<T: Real> Vec2<T> {
+    pub fn new(x: T, y: T) -> Vec2<T> {
+        Vec2 {
             x,
             y
         }
     }
+
+    pub fn dot(&self, vec: &Vec2<T>) -> T {
+        self.x * vec.x + self.y * vec.y
+    }
+
+    // The 2D cross product is the z component of the 3D cross product of the two vectors lying in the XY plane.
+    // It is positive when "vec" is counter-clockwise from "self".
+    pub fn cross(&self, vec: &Vec2<T>) -> T {
+        self.x * vec.y - self.y * vec.x
+    }
+
+    // The vector rotated 90 degrees counter-clockwise, which is perpendicular to it and of the same length.
+    pub fn perp(&self) -> Vec2<T> {
+        Vec2::new(-self.y, self.x)
+    }
+
+    // Rotates the vector counter-clockwise by the angle.
+    pub fn rotate(&self, angle_in_radians: T) -> Vec2<T> {
+        let (sin, cos) = math::sin_cos(angle_in_radians);
+
+        Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    // The angle from the positive X axis to the vector, counter-clockwise in [-pi, pi].
+    pub fn angle(&self) -> T {
+        math::atan2(self.y, self.x)
+    }
+
+    // The signed angle to rotate "self" by to point in the direction of "vec", in [-pi, pi].
+    pub fn angle_to(&self, vec: &Vec2<T>) -> T {
+        math::atan2(self.cross(vec), self.dot(vec))
+    }
 }
 
-#[derive(Default, Clone, Copy)]
-pub struct Vector3
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Vec3<T>
 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32
+    pub x: T,
+    pub y: T,
+    pub z: T
 }
 
-impl fmt::Debug for Vector3 {
+impl<T: Real> fmt::Debug for Vec3<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Vector3")
             .field("x", &self.x)
@@ -49,29 +182,39 @@ impl fmt::Debug for Vector3 {
     }
 }
 
-impl Vector3 {
-    pub fn new(x: f32, y: f32, z: f32) -> Vector3 {
-        Vector3 {
+impl<T: Real> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vec3<T> {
+        Vec3 {
             x,
             y,
             z
         }
     }
 
-    pub fn dot(&self, vec: &Vector3) -> f32 {
+    pub fn dot(&self, vec: &Vec3<T>) -> T {
         self.x * vec.x + self.y * vec.y + self.z * vec.z
     }
+
+    // The cross product gives a vector perpendicular to both vectors.
+    // Since the library is left-handed, x cross y gives z pointing into the screen.
+    pub fn cross(&self, vec: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * vec.z - self.z * vec.y,
+            self.z * vec.x - self.x * vec.z,
+            self.x * vec.y - self.y * vec.x
+        )
+    }
 }
 
-#[derive(Default, Clone, Copy)]
-pub struct Vector4 {
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
-    pub w: f32
+#[derive(Default, Clone, Copy, PartialEq)]
+pub struct Vec4<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T
 }
 
-impl fmt::Debug for Vector4 {
+impl<T: Real> fmt::Debug for Vec4<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Vector4")
             .field("x", &self.x)
@@ -82,9 +225,9 @@ impl fmt::Debug for Vector4 {
     }
 }
 
-impl Vector4 {
-    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Vector4 {
-        Vector4 {
+impl<T: Real> Vec4<T> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Vec4<T> {
+        Vec4 {
             x,
             y,
             z,
@@ -92,313 +235,1714 @@ impl Vector4 {
         }
     }
 
-    pub fn dot(&self, vec: &Vector4) -> f32 {
+    pub fn dot(&self, vec: &Vec4<T>) -> T {
         self.x * vec.x + self.y * vec.y + self.z * vec.z + self.w * vec.w
     }
 
-    pub fn magnitude(&self) -> f32 {
-        ( self.x.powf(2.0) + self.y.powf(2.0) + self.z.powf(2.0) + self.w.powf(2.0) ).sqrt()
+    pub fn magnitude(&self) -> T {
+        ( self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w ).sqrt()
     }
 
-    pub fn normalize(&self) -> Vector4 {
-        Vector4::new( self.x / self.magnitude(), self.y / self.magnitude(), self.z / self.magnitude(), self.w / self.magnitude() )
+    // The cross product is only defined in three dimensions, so the w component is ignored and the result is a direction (w = 0).
+    pub fn cross(&self, vec: &Vec4<T>) -> Vec4<T> {
+        Vec4::new(
+            self.y * vec.z - self.z * vec.y,
+            self.z * vec.x - self.x * vec.z,
+            self.x * vec.y - self.y * vec.x,
+            T::zero()
+        )
     }
 
-    pub fn as_array(&self) -> [f32; 4] {
+    pub fn as_array(&self) -> [T; 4] {
         [self.x, self.y, self.z, self.w]
     }
 }
 
-pub struct Mat4
+// Operators and the vector algebra shared by Vector2, Vector3 and Vector4.
+// They are all implemented component-wise, so a macro saves writing the same code three times.
+macro_rules! impl_vector_algebra {
+    ($vector:ident { $($field:ident),+ }) => {
+        impl<T: Real> Add for $vector<T> {
+            type Output = $vector<T>;
+
+            fn add(self, rhs: $vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Real> Sub for $vector<T> {
+            type Output = $vector<T>;
+
+            fn sub(self, rhs: $vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Real> Mul<T> for $vector<T> {
+            type Output = $vector<T>;
+
+            fn mul(self, rhs: T) -> $vector<T> {
+                $vector { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        // Rust does not allow implementing an operator for every scalar type on the left hand side at once,
+        // so scalar * vector is implemented for each precision.
+        impl Mul<$vector<f32>> for f32 {
+            type Output = $vector<f32>;
+
+            fn mul(self, rhs: $vector<f32>) -> $vector<f32> {
+                rhs * self
+            }
+        }
+
+        impl Mul<$vector<f64>> for f64 {
+            type Output = $vector<f64>;
+
+            fn mul(self, rhs: $vector<f64>) -> $vector<f64> {
+                rhs * self
+            }
+        }
+
+        impl<T: Real> Div<T> for $vector<T> {
+            type Output = $vector<T>;
+
+            fn div(self, rhs: T) -> $vector<T> {
+                $vector { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl<T: Real> Neg for $vector<T> {
+            type Output = $vector<T>;
+
+            fn neg(self) -> $vector<T> {
+                $vector { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Real> AddAssign for $vector<T> {
+            fn add_assign(&mut self, rhs: $vector<T>) {
+                $(self.$field += rhs.$field;)+
+            }
+        }
+
+        impl<T: Real> SubAssign for $vector<T> {
+            fn sub_assign(&mut self, rhs: $vector<T>) {
+                $(self.$field -= rhs.$field;)+
+            }
+        }
+
+        impl<T: Real> MulAssign<T> for $vector<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                $(self.$field *= rhs;)+
+            }
+        }
+
+        impl<T: Real> DivAssign<T> for $vector<T> {
+            fn div_assign(&mut self, rhs: T) {
+                $(self.$field /= rhs;)+
+            }
+        }
+
+        // Displays the vector as (x, y, z).
+        impl<T: Real> fmt::Display for $vector<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "(")?;
+                write_separated(f, &[$(self.$field),+])?;
+                write!(f, ")")
+            }
+        }
+
+        // Going from single to double precision never loses information, so it is available as a plain conversion.
+        impl From<$vector<f32>> for $vector<f64> {
+            fn from(vec: $vector<f32>) -> $vector<f64> {
+                vec.cast()
+            }
+        }
+
+        impl<T: Real> $vector<T> {
+            pub fn length_squared(&self) -> T {
+                self.dot(self)
+            }
+
+            pub fn length(&self) -> T {
+                self.length_squared().sqrt()
+            }
+
+            // Normalizing a zero length vector gives NaN components, the same as dividing by zero would.
+            pub fn normalize(&self) -> $vector<T> {
+                *self / self.length()
+            }
+
+            pub fn distance(&self, vec: &$vector<T>) -> T {
+                (*vec - *self).length()
+            }
+
+            // Linear interpolation, giving "self" when t = 0.0 and "vec" when t = 1.0.
+            pub fn lerp(&self, vec: &$vector<T>, t: T) -> $vector<T> {
+                *self + (*vec - *self) * t
+            }
+
+            // Reflects the vector about a plane with the given normal. The normal is expected to be of unit length.
+            pub fn reflect(&self, normal: &$vector<T>) -> $vector<T> {
+                *self - *normal * (T::from_f64(2.0) * self.dot(normal))
+            }
+
+            // Projects the vector onto "vec". "vec" does not need to be of unit length, but must not be zero.
+            pub fn project_on(&self, vec: &$vector<T>) -> $vector<T> {
+                *vec * (self.dot(vec) / vec.length_squared())
+            }
+
+            pub fn min(&self, vec: &$vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field.min(vec.$field)),+ }
+            }
+
+            pub fn max(&self, vec: &$vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field.max(vec.$field)),+ }
+            }
+
+            pub fn clamp(&self, min: &$vector<T>, max: &$vector<T>) -> $vector<T> {
+                self.max(min).min(max)
+            }
+
+            // Converts to another precision. Going from f64 to f32 rounds every component to the nearest f32.
+            pub fn cast<U: Real>(&self) -> $vector<U> {
+                $vector { $($field: U::from_f64(self.$field.into_f64())),+ }
+            }
+        }
+    };
+}
+
+impl_vector_algebra!(Vec2 { x, y });
+impl_vector_algebra!(Vec3 { x, y, z });
+impl_vector_algebra!(Vec4 { x, y, z, w });
+
+// Describes how a projection matrix maps view space depth into the [0, 1] depth range of the depth buffer.
+// With Reversed, the near plane maps to 1.0 and the far plane to 0.0. Since floating point numbers are much more precise
+// close to 0.0, this spreads the depth precision almost evenly over the view distance when used with a floating point depth buffer.
+// A reversed depth range requires the depth buffer to be cleared to 0.0 and the depth test to use GREATER instead of LESS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepthRange {
+    Standard,
+    Reversed
+}
+
+impl DepthRange {
+    // The value the depth buffer should be cleared to, which is the depth of the far plane.
+    pub fn clear_depth(&self) -> f32 {
+        match self {
+            DepthRange::Standard => 1.0,
+            DepthRange::Reversed => 0.0
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Matrix4<T>
 {
-    matrix: [f32; 16]
+    matrix: [T; 16]
 }
 
-impl fmt::Debug for Mat4 {
+impl<T: Real> fmt::Debug for Matrix4<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}\n{:?}\n{:?}\n{:?}\n", 
-            Vector4::new(self.get(0, 0), self.get(1, 0), self.get(2, 0), self.get(3, 0)),
-            Vector4::new(self.get(0, 1), self.get(1, 1), self.get(2, 1), self.get(3, 1)),
-            Vector4::new(self.get(0, 2), self.get(1, 2), self.get(2, 2), self.get(3, 2)),
-            Vector4::new(self.get(0, 3), self.get(1, 3), self.get(2, 3), self.get(3, 3)))
+            Vec4::new(self.get(0, 0), self.get(1, 0), self.get(2, 0), self.get(3, 0)),
+            Vec4::new(self.get(0, 1), self.get(1, 1), self.get(2, 1), self.get(3, 1)),
+            Vec4::new(self.get(0, 2), self.get(1, 2), self.get(2, 2), self.get(3, 2)),
+            Vec4::new(self.get(0, 3), self.get(1, 3), self.get(2, 3), self.get(3, 3)))
+    }
+}
+
+// Displays one row per line, as [m11, m12, m13, m14].
+impl<T: Real> fmt::Display for Matrix4<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rows(f, &self.matrix, 4)
     }
 }
 
+fn write_rows<T: Real>(f: &mut fmt::Formatter<'_>, matrix: &[T], row_length: usize) -> fmt::Result {
+    for (index, row) in matrix.chunks(row_length).enumerate() {
+        if index > 0 {
+            writeln!(f)?;
+        }
+
+        write!(f, "[")?;
+        write_separated(f, row)?;
+        write!(f, "]")?;
+    }
+
+    Ok(())
+}
+
 // Methods in Rust are defined within an "implementation block" for a specified struct.
-impl Mat4
+impl<T: Real> Matrix4<T>
 {
     // An "associated function" is like a static method, in that it is not associated with a specific instance of a struct,
     // but associated with the type itself.
-    pub fn new(matrix: [f32; 16]) -> Mat4 {
-        Mat4 {
+    pub fn new(matrix: [T; 16]) -> Matrix4<T> {
+        Matrix4 {
             matrix
         }
     }
 
-    pub fn get(&self, x: i32, y: i32) -> f32 {
+    pub fn get(&self, x: i32, y: i32) -> T {
         let index : usize = (x + (4 * y)) as usize;
         self.matrix[index]
     }
 
-    pub fn identity() -> Mat4 {
-        Mat4 {
+    pub fn identity() -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        Matrix4 {
             matrix: [
-                1.0, 0.0, 0.0, 0.0,
-                0.0, 1.0, 0.0, 0.0,
-                0.0, 0.0, 1.0, 0.0,
-                0.0, 0.0, 0.0, 1.0
+                one , zero, zero, zero,
+                zero, one , zero, zero,
+                zero, zero, one , zero,
+                zero, zero, zero, one
             ]
         }
     }
 
-    pub fn translate(pos: &Vector3) -> Mat4 {
-        Mat4 {
+    pub fn translate(pos: &Vec3<T>) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        Matrix4 {
             matrix: [
-                1.0  , 0.0  , 0.0  , 0.0,
-                0.0  , 1.0  , 0.0  , 0.0,
-                0.0  , 0.0  , 1.0  , 0.0,
-                pos.x  , pos.y  , pos.z , 1.0
+                one    , zero   , zero   , zero,
+                zero   , one    , zero   , zero,
+                zero   , zero   , one    , zero,
+                pos.x  , pos.y  , pos.z  , one
             ]
         }
     }
 
-    pub fn scale(scale: &Vector3) -> Mat4 {
-        Mat4 {
+    pub fn scale(scale: &Vec3<T>) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        Matrix4 {
             matrix: [
-                scale.x, 0.0    , 0.0    , 0.0,
-                0.0    , scale.y, 0.0    , 0.0,
-                0.0    , 0.0    , scale.z, 0.0,
-                0.0    , 0.0    , 0.0    , 1.0
+                scale.x, zero   , zero   , zero,
+                zero   , scale.y, zero   , zero,
+                zero   , zero   , scale.z, zero,
+                zero   , zero   , zero   , one
             ]
         }
     }
 
-    pub fn rotate_x(rad: f32) -> Mat4 {
-        Mat4 {
+    pub fn rotate_x(rad: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let (sin, cos) = math::sin_cos(rad);
+
+        Matrix4 {
             matrix: [
-                1.0, 0.0, 0.0, 0.0,
-                0.0, rad.cos(), rad.sin(), 0.0,
-                0.0, -rad.sin(), rad.cos(), 0.0,
-                0.0, 0.0, 0.0, 1.0
+                one, zero, zero, zero,
+                zero, cos, sin, zero,
+                zero, -sin, cos, zero,
+                zero, zero, zero, one
             ]
         }
     }
 
-    pub fn rotate_y(rad: f32) -> Mat4 {
-        Mat4 {
+    pub fn rotate_y(rad: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let (sin, cos) = math::sin_cos(rad);
+
+        Matrix4 {
             matrix: [
-                rad.cos() , 0.0 , -rad.sin() , 0.0,
-                0.0       , 1.0 , 0.0       , 0.0,
-                rad.sin(), 0.0 , rad.cos() , 0.0,
-                0.0       , 0.0 , 0.0       , 1.0
+                cos  , zero , -sin , zero,
+                zero , one  , zero , zero,
+                sin  , zero , cos  , zero,
+                zero , zero , zero , one
             ]
         }
     }
 
-    pub fn rotate_z(rad: f32) -> Mat4 {
-        Mat4 {
+    pub fn rotate_z(rad: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let (sin, cos) = math::sin_cos(rad);
+
+        Matrix4 {
             matrix: [
-                rad.cos(), -rad.sin(), 0.0, 0.0,
-                rad.sin(), rad.cos(), 0.0, 0.0,
-                0.0, 0.0, 1.0, 0.0,
-                0.0, 0.0, 0.0, 1.0
+                cos, -sin, zero, zero,
+                sin, cos, zero, zero,
+                zero, zero, one, zero,
+                zero, zero, zero, one
             ]
         }
     }
 
     pub fn tranpose(&mut self) {
-        self.matrix = self.get_column_major_value()
+        self.matrix = T::mat4_transpose(self).matrix
     }
 
     // For this projection matrix, I use what is sometimes referred to as the Hor+ scaling method for Field of View (https://en.wikipedia.org/wiki/Field_of_view_in_video_games).
     // Basically, the vertical FoV is fixed, while the horizontal FoV scales with the aspect ratio.
-    pub fn projection(fov: f32, width: f32, height: f32, near: f32, far: f32) -> Mat4 {
-        let y_scale = 1.0 / (fov * 0.5).tan();
+    pub fn projection(fov: T, width: T, height: T, near: T, far: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        let y_scale = one / math::tan(fov * T::from_f64(0.5));
+        let x_scale = y_scale / (width / height);
+        let q = far / (far - near);
+
+        Matrix4 {
+            matrix: [
+                x_scale, zero   , zero     , zero,
+                zero   , y_scale, zero     , zero,
+                zero   , zero   , q        , one,
+                zero   , zero   , -q * near, zero
+            ]
+        }
+    }
+
+    // Same as "projection", but with the depth range given explicitly.
+    // After the perspective divide, depth is (a * z + b) / z, so a and b are picked to map near and far to the ends of the depth range.
+    pub fn projection_with_depth_range(fov: T, width: T, height: T, near: T, far: T, depth_range: DepthRange) -> Matrix4<T> {
+        let (a, b) = match depth_range {
+            DepthRange::Standard => (far / (far - near), -near * far / (far - near)),
+            DepthRange::Reversed => (near / (near - far), near * far / (far - near))
+        };
+
+        Matrix4::perspective(fov, width, height, a, b)
+    }
+
+    // Perspective projection with the far plane placed at infinity, which is the limit of "projection" as far goes to infinity.
+    // Nothing is clipped by the far plane, which is useful when drawing everything from orbit down to the surface.
+    pub fn projection_infinite(fov: T, width: T, height: T, near: T, depth_range: DepthRange) -> Matrix4<T> {
+        let (a, b) = match depth_range {
+            DepthRange::Standard => (T::one(), -near),
+            DepthRange::Reversed => (T::zero(), near)
+        };
+
+        Matrix4::perspective(fov, width, height, a, b)
+    }
+
+    fn perspective(fov: T, width: T, height: T, a: T, b: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        let y_scale = one / math::tan(fov * T::from_f64(0.5));
         let x_scale = y_scale / (width / height);
+
+        Matrix4 {
+            matrix: [
+                x_scale, zero   , zero, zero,
+                zero   , y_scale, zero, zero,
+                zero   , zero   , a   , one,
+                zero   , zero   , b   , zero
+            ]
+        }
+    }
+
+    // Perspective projection where the view volume does not have to be centered on the Z axis.
+    // left, right, bottom and top are the extents of the view volume at the near plane.
+    pub fn projection_off_center(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let two = T::from_f64(2.0);
+
         let q = far / (far - near);
 
-        Mat4 {
+        Matrix4 {
+            matrix: [
+                two * near / (right - left)    , zero                           , zero     , zero,
+                zero                           , two * near / (top - bottom)    , zero     , zero,
+                (left + right) / (left - right), (top + bottom) / (bottom - top), q        , one,
+                zero                           , zero                           , -q * near, zero
+            ]
+        }
+    }
+
+    // Orthographic projection mapping the box given by left/right, bottom/top and near/far to X and Y in [-1, 1] and Z in [0, 1].
+    // Useful for 2D drawing such as a HUD, where left = 0, right = width, bottom = 0 and top = height lets you work in pixels.
+    pub fn orthographic(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let two = T::from_f64(2.0);
+
+        Matrix4 {
             matrix: [
-                x_scale, 0.0    , 0.0      , 0.0,
-                0.0    , y_scale, 0.0      , 0.0,
-                0.0    , 0.0    , q        , 1.0,
-                0.0    , 0.0    , -q * near, 0.0
+                two / (right - left)           , zero                           , zero                , zero,
+                zero                           , two / (top - bottom)           , zero                , zero,
+                zero                           , zero                           , one / (far - near)  , zero,
+                (left + right) / (left - right), (top + bottom) / (bottom - top), near / (near - far) , one
             ]
         }
     }
 
-    pub fn mul(&self, mat: &Mat4) -> Mat4 {
-        let self_row0 = Vector4::new(self.get(0, 0), self.get(1, 0),  self.get(2, 0), self.get(3, 0));
-        let self_row1 = Vector4::new(self.get(0, 1), self.get(1, 1),  self.get(2, 1), self.get(3, 1));
-        let self_row2 = Vector4::new(self.get(0, 2), self.get(1, 2),  self.get(2, 2), self.get(3, 2));
-        let self_row3 = Vector4::new(self.get(0, 3), self.get(1, 3),  self.get(2, 3), self.get(3, 3));
+    // View matrix for a camera at "eye" looking at "target", in a left-handed coordinate system (Z pointing away from the camera).
+    // The rows of the upper 3x3 part are the transposed camera axes, which is the inverse of the camera rotation.
+    pub fn look_at_lh(eye: &Vec3<T>, target: &Vec3<T>, up: &Vec3<T>) -> Matrix4<T> {
+        Matrix4::look_to(eye, &(*target - *eye), up)
+    }
+
+    // View matrix for a camera at "eye" looking at "target", in a right-handed coordinate system (Z pointing towards the camera).
+    pub fn look_at_rh(eye: &Vec3<T>, target: &Vec3<T>, up: &Vec3<T>) -> Matrix4<T> {
+        Matrix4::look_to(eye, &(*eye - *target), up)
+    }
+
+    fn look_to(eye: &Vec3<T>, z_direction: &Vec3<T>, up: &Vec3<T>) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
 
-        let mat_column0 = Vector4::new( mat.get(0, 0), mat.get(0, 1), mat.get(0, 2), mat.get(0, 3));
-        let mat_column1 = Vector4::new( mat.get(1, 0), mat.get(1, 1), mat.get(1, 2), mat.get(1, 3));
-        let mat_column2 = Vector4::new( mat.get(2, 0), mat.get(2, 1), mat.get(2, 2), mat.get(2, 3));
-        let mat_column3 = Vector4::new( mat.get(3, 0), mat.get(3, 1), mat.get(3, 2), mat.get(3, 3));
+        let z_axis = z_direction.normalize();
+        let x_axis = up.cross(&z_axis).normalize();
+        let y_axis = z_axis.cross(&x_axis);
 
-        Mat4 {
+        Matrix4 {
             matrix: [
-                self_row0.dot(&mat_column0), self_row0.dot(&mat_column1), self_row0.dot(&mat_column2), self_row0.dot(&&mat_column3),
-                self_row1.dot(&mat_column0), self_row1.dot(&mat_column1), self_row1.dot(&mat_column2), self_row1.dot(&&mat_column3),
-                self_row2.dot(&mat_column0), self_row2.dot(&mat_column1), self_row2.dot(&mat_column2), self_row2.dot(&&mat_column3),
-                self_row3.dot(&mat_column0), self_row3.dot(&mat_column1), self_row3.dot(&mat_column2), self_row3.dot(&&mat_column3),
+                x_axis.x          , y_axis.x          , z_axis.x          , zero,
+                x_axis.y          , y_axis.y          , z_axis.y          , zero,
+                x_axis.z          , y_axis.z          , z_axis.z          , zero,
+                -x_axis.dot(eye)  , -y_axis.dot(eye)  , -z_axis.dot(eye)  , one
             ]
         }
     }
 
-    pub fn get_value(&self) -> [f32; 16] {
+    // Uses SIMD instructions when available for the scalar type, see the "simd" module.
+    pub fn mul(&self, mat: &Matrix4<T>) -> Matrix4<T> {
+        T::mat4_mul(self, mat)
+    }
+
+    // Transforms a point (w = 1), including the translation of the matrix.
+    // For projection matrices the result is divided by w (the perspective divide), giving normalized device coordinates.
+    // Points with w = 0 after the transform, that is, on the camera plane, give infinite or NaN components.
+    pub fn transform_point(&self, point: &Vec3<T>) -> Vec3<T> {
+        let result = Vec4::new(point.x, point.y, point.z, T::one()) * *self;
+
+        if result.w == T::one() {
+            Vec3::new(result.x, result.y, result.z)
+        } else {
+            Vec3::new(result.x / result.w, result.y / result.w, result.z / result.w)
+        }
+    }
+
+    // Transforms a direction (w = 0), which is not affected by the translation of the matrix.
+    // Note that normals should be transformed by the normal matrix instead, see "normal_matrix".
+    pub fn transform_direction(&self, direction: &Vec3<T>) -> Vec3<T> {
+        let result = Vec4::new(direction.x, direction.y, direction.z, T::zero()) * *self;
+
+        Vec3::new(result.x, result.y, result.z)
+    }
+
+    pub fn transform_points(&self, points: &[Vec3<T>]) -> Vec<Vec3<T>> {
+        T::mat4_transform_points(self, points)
+    }
+
+    pub fn transform_directions(&self, directions: &[Vec3<T>]) -> Vec<Vec3<T>> {
+        directions.iter().map(|direction| self.transform_direction(direction)).collect()
+    }
+
+    pub fn get_value(&self) -> [T; 16] {
         self.matrix
     }
 
-    pub fn get_column_major_value(&self) -> [f32; 16] {
-        [
-            self.matrix[0], self.matrix[4], self.matrix[8], self.matrix[12],
-            self.matrix[1], self.matrix[5], self.matrix[9], self.matrix[13],
-            self.matrix[2], self.matrix[6], self.matrix[10], self.matrix[14],
-            self.matrix[3], self.matrix[7], self.matrix[11], self.matrix[15]
-        ]
+    pub fn get_column_major_value(&self) -> [T; 16] {
+        T::mat4_transpose(self).matrix
     }
-}
 
-pub struct Quaternion {
-    quat: Vector4
-}
+    // The determinant is calculated by Laplace expansion, reusing the 2x2 sub-determinants of the upper two rows (s) and the lower two rows (c).
+    pub fn determinant(&self) -> T {
+        let (s, c) = self.sub_determinants();
+
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
 
-impl Quaternion {
-    pub fn Rotation(axis: Vector3, angle_in_radians: f32) -> Mat4 {
-        let angle = angle_in_radians / 2.0;
+    // General inverse through the adjugate matrix divided by the determinant.
+    // Returns None if the matrix is singular (its determinant is zero), as it then has no inverse.
+    pub fn inverse(&self) -> Option<Matrix4<T>> {
+        T::mat4_inverse(self)
+    }
+
+    // Fast inverse for affine matrices, that is, matrices built from translation, rotation and scale where the last column is (0, 0, 0, 1).
+    // Since my matrices are multiplied with row vectors, an affine matrix looks like [M 0; t 1], and its inverse is [M^-1 0; -tM^-1 1].
+    // Only the upper 3x3 part needs a real inverse. Using this on a matrix that is not affine (such as a projection) gives a wrong result.
+    pub fn inverse_affine(&self) -> Option<Matrix4<T>> {
+        let zero = T::zero();
+        let one = T::one();
 
-        let mut q = Vector4::new(
-            angle.sin() * axis.x,
-            angle.sin() * axis.y,
-            angle.sin() * axis.z,
-            angle.cos()
-        );
+        let inverse = Matrix3::from_mat4(self).inverse()?;
+        let i = &inverse.matrix;
 
-        q = q.normalize();
+        // -tM^-1 is the translation row multiplied with the inverse 3x3 matrix.
+        let translation = Vec3::new(self.matrix[12], self.matrix[13], self.matrix[14]);
+        let inverse_translation = -(Vec3::new(i[0], i[1], i[2]) * translation.x
+            + Vec3::new(i[3], i[4], i[5]) * translation.y
+            + Vec3::new(i[6], i[7], i[8]) * translation.z);
 
-        let m11 = 1.0 - 2.0 * q.y.powf(2.0) - 2.0 * q.z.powf(2.0);
-        let m12 = 2.0 * q.x * q.y + 2.0 * q.z * q.w;
-        let m13 = 2.0 * q.x * q.z - 2.0 * q.y * q.w;
-        let m14 = 0.0;
+        Some(Matrix4 {
+            matrix: [
+                i[0], i[1], i[2], zero,
+                i[3], i[4], i[5], zero,
+                i[6], i[7], i[8], zero,
+                inverse_translation.x, inverse_translation.y, inverse_translation.z, one
+            ]
+        })
+    }
 
-        let m21 = 2.0 * q.x * q.y - 2.0 * q.z * q.w;
-        let m22 = 1.0 - 2.0 * q.x.powf(2.0) - 2.0 * q.z.powf(2.0);
-        let m23 = 2.0 * q.y * q.z + 2.0 * q.x * q.w;
-        let m24 = 0.0;
+    // The normal matrix is the inverse transpose of the upper 3x3 part of the world matrix.
+    // Normals transformed with the world matrix itself would no longer be perpendicular to the surface under non-uniform scale.
+    // Returns None if the upper 3x3 part is singular.
+    pub fn normal_matrix(&self) -> Option<Matrix3<T>> {
+        Some(Matrix3::from_mat4(self).inverse()?.transpose())
+    }
 
-        let m31 = 2.0 * q.x * q.z + 2.0 * q.y * q.w;
-        let m32 = 2.0 * q.y * q.z - 2.0 * q.x * q.w;
-        let m33 = 1.0 - 2.0 * q.x.powf(2.0) - 2.0 * q.y.powf(2.0);
-        let m34 = 0.0;
+    // Converts to another precision. Going from f64 to f32 rounds every element to the nearest f32.
+    pub fn cast<U: Real>(&self) -> Matrix4<U> {
+        let mut matrix = [U::zero(); 16];
 
-        let m41 = 0.0;
-        let m42 = 0.0;
-        let m43 = 0.0;
-        let m44 = 1.0;
+        for (converted, element) in matrix.iter_mut().zip(self.matrix.iter()) {
+            *converted = U::from_f64(element.into_f64());
+        }
 
-        let m = Mat4::new([
-            m11, m12, m13, m14,
-            m21, m22, m23, m24,
-            m31, m32, m33, m34,
-            m41, m42, m43, m44
-        ]);
+        Matrix4 {
+            matrix
+        }
+    }
 
-        m
+    fn sub_determinants(&self) -> ([T; 6], [T; 6]) {
+        let m = &self.matrix;
+
+        let s = [
+            m[0] * m[5] - m[4] * m[1],
+            m[0] * m[6] - m[4] * m[2],
+            m[0] * m[7] - m[4] * m[3],
+            m[1] * m[6] - m[5] * m[2],
+            m[1] * m[7] - m[5] * m[3],
+            m[2] * m[7] - m[6] * m[3]
+        ];
+
+        let c = [
+            m[8] * m[13] - m[12] * m[9],
+            m[8] * m[14] - m[12] * m[10],
+            m[8] * m[15] - m[12] * m[11],
+            m[9] * m[14] - m[13] * m[10],
+            m[9] * m[15] - m[13] * m[11],
+            m[10] * m[15] - m[14] * m[11]
+        ];
+
+        (s, c)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::beagle_math::*;
+    // The plain implementations of the operations in the "simd" module.
+    // They are used for f64, and for f32 on platforms without a SIMD implementation.
+    fn mul_scalar(&self, mat: &Matrix4<T>) -> Matrix4<T> {
+        let self_row0 = Vec4::new(self.get(0, 0), self.get(1, 0),  self.get(2, 0), self.get(3, 0));
+        let self_row1 = Vec4::new(self.get(0, 1), self.get(1, 1),  self.get(2, 1), self.get(3, 1));
+        let self_row2 = Vec4::new(self.get(0, 2), self.get(1, 2),  self.get(2, 2), self.get(3, 2));
+        let self_row3 = Vec4::new(self.get(0, 3), self.get(1, 3),  self.get(2, 3), self.get(3, 3));
 
-    #[test]
-    fn tmp_quick_tester() {
-        let mat_a = Mat4::new([
-            1.0, 8.0, 2.0, 3.0,
-            2.0, 1.0, 1.0, 1.0,
-            3.0, 2.0, 4.0, 3.0,
-            1.0, 9.0, 4.0, 3.0
-        ]);
+        let mat_column0 = Vec4::new( mat.get(0, 0), mat.get(0, 1), mat.get(0, 2), mat.get(0, 3));
+        let mat_column1 = Vec4::new( mat.get(1, 0), mat.get(1, 1), mat.get(1, 2), mat.get(1, 3));
+        let mat_column2 = Vec4::new( mat.get(2, 0), mat.get(2, 1), mat.get(2, 2), mat.get(2, 3));
+        let mat_column3 = Vec4::new( mat.get(3, 0), mat.get(3, 1), mat.get(3, 2), mat.get(3, 3));
 
-        let mat_b = Mat4::new([
-            8.0, 4.0, 3.0, 2.0,
-            1.0, 1.0, 2.0, 2.0,
-            3.0, 2.0, 1.0, 1.0,
-            1.0, 2.0, 3.0, 4.0
-        ]);
+        Matrix4 {
+            matrix: [
+                self_row0.dot(&mat_column0), self_row0.dot(&mat_column1), self_row0.dot(&mat_column2), self_row0.dot(&mat_column3),
+                self_row1.dot(&mat_column0), self_row1.dot(&mat_column1), self_row1.dot(&mat_column2), self_row1.dot(&mat_column3),
+                self_row2.dot(&mat_column0), self_row2.dot(&mat_column1), self_row2.dot(&mat_column2), self_row2.dot(&mat_column3),
+                self_row3.dot(&mat_column0), self_row3.dot(&mat_column1), self_row3.dot(&mat_column2), self_row3.dot(&mat_column3),
+            ]
+        }
+    }
 
-        let result = mat_a.mul(&&mat_b);
+    fn transpose_scalar(&self) -> Matrix4<T> {
+        Matrix4::new([
+            self.matrix[0], self.matrix[4], self.matrix[8], self.matrix[12],
+            self.matrix[1], self.matrix[5], self.matrix[9], self.matrix[13],
+            self.matrix[2], self.matrix[6], self.matrix[10], self.matrix[14],
+            self.matrix[3], self.matrix[7], self.matrix[11], self.matrix[15]
+        ])
+    }
 
-        let mut rofl = Mat4::new([
-            5.0, 6.0, 1.0, 2.0,
-            3.0, 0.0, 12.0, 2.0,
-            48.0, 38.0, 2.0, 9.0,
-            8.0, 5.0, 4.0, 44.0
-        ]);
+    fn inverse_scalar(&self) -> Option<Matrix4<T>> {
+        let (s, c) = self.sub_determinants();
 
-        rofl.tranpose();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
 
-        println!("{:?}", rofl);
+        if det == T::zero() || !det.is_finite() {
+            return None;
+        }
+
+        let inv_det = T::one() / det;
+        let m = &self.matrix;
+
+        Some(Matrix4 {
+            matrix: [
+                ( m[5] * c[5] - m[6] * c[4] + m[7] * c[3]) * inv_det,
+                (-m[1] * c[5] + m[2] * c[4] - m[3] * c[3]) * inv_det,
+                ( m[13] * s[5] - m[14] * s[4] + m[15] * s[3]) * inv_det,
+                (-m[9] * s[5] + m[10] * s[4] - m[11] * s[3]) * inv_det,
+
+                (-m[4] * c[5] + m[6] * c[2] - m[7] * c[1]) * inv_det,
+                ( m[0] * c[5] - m[2] * c[2] + m[3] * c[1]) * inv_det,
+                (-m[12] * s[5] + m[14] * s[2] - m[15] * s[1]) * inv_det,
+                ( m[8] * s[5] - m[10] * s[2] + m[11] * s[1]) * inv_det,
+
+                ( m[4] * c[4] - m[5] * c[2] + m[7] * c[0]) * inv_det,
+                (-m[0] * c[4] + m[1] * c[2] - m[3] * c[0]) * inv_det,
+                ( m[12] * s[4] - m[13] * s[2] + m[15] * s[0]) * inv_det,
+                (-m[8] * s[4] + m[9] * s[2] - m[11] * s[0]) * inv_det,
+
+                (-m[4] * c[3] + m[5] * c[1] - m[6] * c[0]) * inv_det,
+                ( m[0] * c[3] - m[1] * c[1] + m[2] * c[0]) * inv_det,
+                (-m[12] * s[3] + m[13] * s[1] - m[14] * s[0]) * inv_det,
+                ( m[8] * s[3] - m[9] * s[1] + m[10] * s[0]) * inv_det
+            ]
+        })
     }
 
-    #[test]
-    fn should_print_matrix_debug_output_when_using_debug_formatter() {
-        let my_matrix = Mat4::identity();
+    fn transform_points_scalar(&self, points: &[Vec3<T>]) -> Vec<Vec3<T>> {
+        points.iter().map(|point| self.transform_point(point)).collect()
+    }
+}
 
-        print!("{:?}", my_matrix);
+impl From<Matrix4<f32>> for Matrix4<f64> {
+    fn from(mat: Matrix4<f32>) -> Matrix4<f64> {
+        mat.cast()
+    }
+}
+
+// Since my matrices are designed for row vectors, the vector goes in front of the matrix: v * M.
+// Each component of the result is the dot product of the vector with a column of the matrix.
+impl<T: Real> Mul<Matrix4<T>> for Vec4<T> {
+    type Output = Vec4<T>;
+
+    fn mul(self, rhs: Matrix4<T>) -> Vec4<T> {
+        let m = &rhs.matrix;
 
-        assert!(true);
+        Vec4::new(
+            self.x * m[0] + self.y * m[4] + self.z * m[8] + self.w * m[12],
+            self.x * m[1] + self.y * m[5] + self.z * m[9] + self.w * m[13],
+            self.x * m[2] + self.y * m[6] + self.z * m[10] + self.w * m[14],
+            self.x * m[3] + self.y * m[7] + self.z * m[11] + self.w * m[15]
+        )
     }
+}
 
-    #[test]
-    fn should_return_correct_identity_matrix_when_constructing_identity_matrix() {
-        // Arrange
-        let expected_matrix: [f32; 16] = [1.0, 0.0, 0.0, 0.0,
-                                            0.0, 1.0, 0.0, 0.0,
-                                            0.0, 0.0, 1.0, 0.0,
-                                            0.0, 0.0, 0.0, 1.0];
+// A 3x3 matrix, following the same conventions as Mat4: stored in row-major order and multiplied with row vectors.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Matrix3<T>
+{
+    matrix: [T; 9]
+}
 
-        // Act
-        let value =  Mat4::identity().get_value();
+impl<T: Real> fmt::Debug for Matrix3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}\n{:?}\n{:?}\n",
+            Vec3::new(self.get(0, 0), self.get(1, 0), self.get(2, 0)),
+            Vec3::new(self.get(0, 1), self.get(1, 1), self.get(2, 1)),
+            Vec3::new(self.get(0, 2), self.get(1, 2), self.get(2, 2)))
+    }
+}
 
-        // Assert
-        assert!( value.iter().eq(expected_matrix.iter()), "The identity matrix was found to be incorrect!");
+impl<T: Real> fmt::Display for Matrix3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_rows(f, &self.matrix, 3)
     }
+}
 
-    #[test]
-    fn should_return_correct_column_major_matrix_when_getting_column_major_value() {
-        // Arrange
-        let original_matrix = Mat4::new(
-                                    [11.0, 12.0, 13.0, 14.0,
-                                            21.0, 22.0, 23.0, 24.0,
-                                            31.0, 32.0, 33.0, 34.0,
-                                            41.0, 42.0, 43.0, 44.0]);
+impl<T: Real> Matrix3<T>
+{
+    pub fn new(matrix: [T; 9]) -> Matrix3<T> {
+        Matrix3 {
+            matrix
+        }
+    }
 
-        let expected_matrix: [f32; 16] = [11.0, 21.0, 31.0, 41.0,
-                                            12.0, 22.0, 32.0, 42.0,
-                                            13.0, 23.0, 33.0, 43.0,
-                                            14.0, 24.0, 34.0, 44.0];
+    pub fn get(&self, x: i32, y: i32) -> T {
+        let index : usize = (x + (3 * y)) as usize;
+        self.matrix[index]
+    }
 
-        // Act
-        let matrix_in_column_major = original_matrix.get_column_major_value();
+    pub fn identity() -> Matrix3<T> {
+        let zero = T::zero();
+        let one = T::one();
 
-        // Assert
-        assert!( matrix_in_column_major.iter().eq(expected_matrix.iter()) );
+        Matrix3 {
+            matrix: [
+                one , zero, zero,
+                zero, one , zero,
+                zero, zero, one
+            ]
+        }
     }
 
-    #[test]
-    fn should_calculate_magnitude_correctly() {
-        let my_vector = Vector4::new(2.0, 4.0, 7.0, 8.0);
+    // Takes the upper 3x3 part of the matrix, that is, the rotation and scale without the translation.
+    pub fn from_mat4(mat: &Matrix4<T>) -> Matrix3<T> {
+        let m = &mat.matrix;
 
-        let magnitude = my_vector.magnitude();
+        Matrix3 {
+            matrix: [
+                m[0], m[1], m[2],
+                m[4], m[5], m[6],
+                m[8], m[9], m[10]
+            ]
+        }
+    }
 
-        println!("magnitude: {}", magnitude);
+    pub fn from_quaternion(quat: &Quat<T>) -> Matrix3<T> {
+        Matrix3::from_mat4(&quat.to_mat4())
+    }
 
-        let normalized = my_vector.normalize();
+    // Places the matrix in the upper 3x3 part of a 4x4 matrix with no translation.
+    pub fn to_mat4(&self) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let m = &self.matrix;
+
+        Matrix4::new([
+            m[0], m[1], m[2], zero,
+            m[3], m[4], m[5], zero,
+            m[6], m[7], m[8], zero,
+            zero, zero, zero, one
+        ])
+    }
+
+    pub fn mul(&self, mat: &Matrix3<T>) -> Matrix3<T> {
+        let a = &self.matrix;
+        let b = &mat.matrix;
 
-        println!("normalized: {:?}", normalized);
+        let mut result = [T::zero(); 9];
+
+        for row in 0..3 {
+            for column in 0..3 {
+                result[row * 3 + column] = a[row * 3] * b[column] + a[row * 3 + 1] * b[3 + column] + a[row * 3 + 2] * b[6 + column];
+            }
+        }
+
+        Matrix3 {
+            matrix: result
+        }
     }
-}
\ No newline at end of file
+
+    pub fn transpose(&self) -> Matrix3<T> {
+        let m = &self.matrix;
+
+        Matrix3 {
+            matrix: [
+                m[0], m[3], m[6],
+                m[1], m[4], m[7],
+                m[2], m[5], m[8]
+            ]
+        }
+    }
+
+    pub fn determinant(&self) -> T {
+        let (row0, row1, row2) = self.rows();
+
+        row0.dot(&row1.cross(&row2))
+    }
+
+    // The columns of the inverse matrix are the cross products of the rows, divided by the determinant.
+    // Returns None if the matrix is singular.
+    pub fn inverse(&self) -> Option<Matrix3<T>> {
+        let (row0, row1, row2) = self.rows();
+
+        let column0 = row1.cross(&row2);
+        let column1 = row2.cross(&row0);
+        let column2 = row0.cross(&row1);
+
+        let det = row0.dot(&column0);
+
+        if det == T::zero() || !det.is_finite() {
+            return None;
+        }
+
+        let column0 = column0 / det;
+        let column1 = column1 / det;
+        let column2 = column2 / det;
+
+        Some(Matrix3 {
+            matrix: [
+                column0.x, column1.x, column2.x,
+                column0.y, column1.y, column2.y,
+                column0.z, column1.z, column2.z
+            ]
+        })
+    }
+
+    pub fn get_value(&self) -> [T; 9] {
+        self.matrix
+    }
+
+    // HLSL packs every column of a float3x3 in a constant buffer into its own 16 byte register, so each column needs a padding element.
+    // Like with Mat4, HLSL reads matrices in column-major order by default, so the columns are written as the registers.
+    pub fn get_padded_column_major_value(&self) -> [T; 12] {
+        let zero = T::zero();
+        let m = &self.matrix;
+
+        [
+            m[0], m[3], m[6], zero,
+            m[1], m[4], m[7], zero,
+            m[2], m[5], m[8], zero
+        ]
+    }
+
+    // Converts to another precision. Going from f64 to f32 rounds every element to the nearest f32.
+    pub fn cast<U: Real>(&self) -> Matrix3<U> {
+        let mut matrix = [U::zero(); 9];
+
+        for (converted, element) in matrix.iter_mut().zip(self.matrix.iter()) {
+            *converted = U::from_f64(element.into_f64());
+        }
+
+        Matrix3 {
+            matrix
+        }
+    }
+
+    fn rows(&self) -> (Vec3<T>, Vec3<T>, Vec3<T>) {
+        let m = &self.matrix;
+
+        (Vec3::new(m[0], m[1], m[2]), Vec3::new(m[3], m[4], m[5]), Vec3::new(m[6], m[7], m[8]))
+    }
+}
+
+impl From<Matrix3<f32>> for Matrix3<f64> {
+    fn from(mat: Matrix3<f32>) -> Matrix3<f64> {
+        mat.cast()
+    }
+}
+
+// A quaternion is stored as (x, y, z, w), where (x, y, z) is the vector part and w is the scalar part.
+// Quaternions used for rotations are expected to be of unit length.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Quat<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T
+}
+
+impl<T: Real> fmt::Debug for Quat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Quaternion")
+            .field("x", &self.x)
+            .field("y", &self.y)
+            .field("z", &self.z)
+            .field("w", &self.w)
+            .finish()
+    }
+}
+
+// Displays the quaternion as (x, y, z, w).
+impl<T: Real> fmt::Display for Quat<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        write_separated(f, &[self.x, self.y, self.z, self.w])?;
+        write!(f, ")")
+    }
+}
+
+impl<T: Real> Default for Quat<T> {
+    fn default() -> Quat<T> {
+        Quat::identity()
+    }
+}
+
+impl<T: Real> Quat<T> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Quat<T> {
+        Quat {
+            x,
+            y,
+            z,
+            w
+        }
+    }
+
+    pub fn identity() -> Quat<T> {
+        Quat::new(T::zero(), T::zero(), T::zero(), T::one())
+    }
+
+    // q = (sin(angle / 2)n, cos(angle / 2)), where n is the normalized axis.
+    pub fn from_axis_angle(axis: &Vec3<T>, angle_in_radians: T) -> Quat<T> {
+        let half_angle = angle_in_radians / T::from_f64(2.0);
+        let (sin, cos) = math::sin_cos(half_angle);
+        let axis = axis.normalize() * sin;
+
+        Quat::new(axis.x, axis.y, axis.z, cos)
+    }
+
+    // Rotates about the Z axis first, then the Y axis, and lastly the X axis.
+    pub fn from_euler(x_in_radians: T, y_in_radians: T, z_in_radians: T) -> Quat<T> {
+        let zero = T::zero();
+        let one = T::one();
+
+        Quat::from_axis_angle(&Vec3::new(zero, zero, one), z_in_radians)
+            .mul(&Quat::from_axis_angle(&Vec3::new(zero, one, zero), y_in_radians))
+            .mul(&Quat::from_axis_angle(&Vec3::new(one, zero, zero), x_in_radians))
+    }
+
+    // Extracts the rotation from the upper 3x3 part of a matrix.
+    // The matrix is expected to be a pure rotation, that is, orthonormal without scale.
+    pub fn from_mat4(mat: &Matrix4<T>) -> Quat<T> {
+        let one = T::one();
+        let two = T::from_f64(2.0);
+        let quarter = T::from_f64(0.25);
+        let m = &mat.matrix;
+
+        // Since my matrices are multiplied with row vectors, the usual (column vector) formulas are used with the rows and columns swapped.
+        let trace = m[0] + m[5] + m[10];
+
+        let q = if trace > T::zero() {
+            let s = (trace + one).sqrt() * two;
+            Quat::new((m[6] - m[9]) / s, (m[8] - m[2]) / s, (m[1] - m[4]) / s, quarter * s)
+        } else if m[0] > m[5] && m[0] > m[10] {
+            let s = (one + m[0] - m[5] - m[10]).sqrt() * two;
+            Quat::new(quarter * s, (m[4] + m[1]) / s, (m[8] + m[2]) / s, (m[6] - m[9]) / s)
+        } else if m[5] > m[10] {
+            let s = (one + m[5] - m[0] - m[10]).sqrt() * two;
+            Quat::new((m[4] + m[1]) / s, quarter * s, (m[9] + m[6]) / s, (m[8] - m[2]) / s)
+        } else {
+            let s = (one + m[10] - m[0] - m[5]).sqrt() * two;
+            Quat::new((m[8] + m[2]) / s, (m[9] + m[6]) / s, quarter * s, (m[1] - m[4]) / s)
+        };
+
+        q.normalize()
+    }
+
+    pub fn dot(&self, quat: &Quat<T>) -> T {
+        self.x * quat.x + self.y * quat.y + self.z * quat.z + self.w * quat.w
+    }
+
+    pub fn length(&self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Quat<T> {
+        let length = self.length();
+        Quat::new(self.x / length, self.y / length, self.z / length, self.w / length)
+    }
+
+    pub fn conjugate(&self) -> Quat<T> {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    // For unit quaternions the inverse is the same as the conjugate, but this also works for quaternions of any non-zero length.
+    pub fn inverse(&self) -> Quat<T> {
+        let length_squared = self.dot(self);
+        let conjugate = self.conjugate();
+
+        Quat::new(conjugate.x / length_squared, conjugate.y / length_squared, conjugate.z / length_squared, conjugate.w / length_squared)
+    }
+
+    // To follow the same order as my row-vector matrices, "a.mul(b)" is the rotation "a" FOLLOWED by the rotation "b".
+    // That is, a.mul(b).to_mat4() is the same as a.to_mat4().mul(b.to_mat4()).
+    // This is the Hamilton product b * a.
+    pub fn mul(&self, quat: &Quat<T>) -> Quat<T> {
+        let a = quat;
+        let b = self;
+
+        Quat::new(
+            a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+            a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z
+        )
+    }
+
+    // Rotates the vector v using the formula q * v * q^-1, expanded so no temporary quaternions are needed.
+    pub fn rotate_vector(&self, v: &Vec3<T>) -> Vec3<T> {
+        let u = Vec3::new(self.x, self.y, self.z);
+        let t = u.cross(v) * T::from_f64(2.0);
+
+        *v + t * self.w + u.cross(&t)
+    }
+
+    // Normalized linear interpolation. Cheaper than slerp, but the rotation speed is not constant over t.
+    pub fn nlerp(&self, quat: &Quat<T>, t: T) -> Quat<T> {
+        // q and -q represent the same rotation, so pick the one giving the shortest path.
+        let sign = if self.dot(quat) < T::zero() { -T::one() } else { T::one() };
+
+        Quat::new(
+            self.x + (quat.x * sign - self.x) * t,
+            self.y + (quat.y * sign - self.y) * t,
+            self.z + (quat.z * sign - self.z) * t,
+            self.w + (quat.w * sign - self.w) * t
+        ).normalize()
+    }
+
+    // Spherical linear interpolation, rotating with constant angular speed along the shortest path.
+    pub fn slerp(&self, quat: &Quat<T>, t: T) -> Quat<T> {
+        let mut cos_theta = self.dot(quat);
+        let mut end = *quat;
+
+        if cos_theta < T::zero() {
+            cos_theta = -cos_theta;
+            end = Quat::new(-quat.x, -quat.y, -quat.z, -quat.w);
+        }
+
+        // When the quaternions are almost the same, sin(theta) approaches zero, so fall back to nlerp.
+        if cos_theta > T::from_f64(0.9995) {
+            return self.nlerp(&end, t);
+        }
+
+        let theta = math::acos(cos_theta);
+        let sin_theta = math::sin(theta);
+        let start_weight = math::sin((T::one() - t) * theta) / sin_theta;
+        let end_weight = math::sin(t * theta) / sin_theta;
+
+        Quat::new(
+            self.x * start_weight + end.x * end_weight,
+            self.y * start_weight + end.y * end_weight,
+            self.z * start_weight + end.z * end_weight,
+            self.w * start_weight + end.w * end_weight
+        )
+    }
+
+    pub fn to_mat4(&self) -> Matrix4<T> {
+        let zero = T::zero();
+        let one = T::one();
+        let two = T::from_f64(2.0);
+        let q = self;
+
+        let m11 = one - two * q.y * q.y - two * q.z * q.z;
+        let m12 = two * q.x * q.y + two * q.z * q.w;
+        let m13 = two * q.x * q.z - two * q.y * q.w;
+        let m14 = zero;
+
+        let m21 = two * q.x * q.y - two * q.z * q.w;
+        let m22 = one - two * q.x * q.x - two * q.z * q.z;
+        let m23 = two * q.y * q.z + two * q.x * q.w;
+        let m24 = zero;
+
+        let m31 = two * q.x * q.z + two * q.y * q.w;
+        let m32 = two * q.y * q.z - two * q.x * q.w;
+        let m33 = one - two * q.x * q.x - two * q.y * q.y;
+        let m34 = zero;
+
+        let m41 = zero;
+        let m42 = zero;
+        let m43 = zero;
+        let m44 = one;
+
+        Matrix4::new([
+            m11, m12, m13, m14,
+            m21, m22, m23, m24,
+            m31, m32, m33, m34,
+            m41, m42, m43, m44
+        ])
+    }
+
+    // Converts to another precision. Going from f64 to f32 rounds every component to the nearest f32.
+    pub fn cast<U: Real>(&self) -> Quat<U> {
+        Quat::new(U::from_f64(self.x.into_f64()), U::from_f64(self.y.into_f64()), U::from_f64(self.z.into_f64()), U::from_f64(self.w.into_f64()))
+    }
+}
+
+impl From<Quat<f32>> for Quat<f64> {
+    fn from(quat: Quat<f32>) -> Quat<f64> {
+        quat.cast()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    #[test]
+    fn should_write_rows_when_using_debug_formatter() {
+        // Arrange
+        let matrix = Mat4::translate(&Vector3::new(1.0, 2.0, 3.0));
+
+        // Act
+        let output = format!("{:?}", matrix);
+
+        // Assert
+        assert_eq!(output.lines().count(), 4);
+        assert_eq!(output.lines().last(), Some("Vector4 { x: 1.0, y: 2.0, z: 3.0, w: 1.0 }"));
+    }
+
+    #[test]
+    fn should_pass_precision_on_to_components_when_using_display_formatter() {
+        // Arrange
+        let vector = Vector3::new(1.0, -2.5, 1.0 / 3.0);
+        let quaternion = Quaternion::identity();
+        let matrix = Mat3::identity();
+
+        // Act
+        let vector_output = format!("{:.2}", vector);
+        let quaternion_output = format!("{}", quaternion);
+        let matrix_output = format!("{:.1}", matrix);
+
+        // Assert
+        assert_eq!(vector_output, "(1.00, -2.50, 0.33)");
+        assert_eq!(quaternion_output, "(0, 0, 0, 1)");
+        assert_eq!(matrix_output, "[1.0, 0.0, 0.0]\n[0.0, 1.0, 0.0]\n[0.0, 0.0, 1.0]");
+    }
+
+    #[test]
+    fn should_return_correct_identity_matrix_when_constructing_identity_matrix() {
+        // Arrange
+        let expected_matrix: [f32; 16] = [1.0, 0.0, 0.0, 0.0,
+                                            0.0, 1.0, 0.0, 0.0,
+                                            0.0, 0.0, 1.0, 0.0,
+                                            0.0, 0.0, 0.0, 1.0];
+
+        // Act
+        let value =  Mat4::identity().get_value();
+
+        // Assert
+        assert!( value.iter().eq(expected_matrix.iter()), "The identity matrix was found to be incorrect!");
+    }
+
+    #[test]
+    fn should_return_correct_column_major_matrix_when_getting_column_major_value() {
+        // Arrange
+        let original_matrix = Mat4::new(
+                                    [11.0, 12.0, 13.0, 14.0,
+                                            21.0, 22.0, 23.0, 24.0,
+                                            31.0, 32.0, 33.0, 34.0,
+                                            41.0, 42.0, 43.0, 44.0]);
+
+        let expected_matrix: [f32; 16] = [11.0, 21.0, 31.0, 41.0,
+                                            12.0, 22.0, 32.0, 42.0,
+                                            13.0, 23.0, 33.0, 43.0,
+                                            14.0, 24.0, 34.0, 44.0];
+
+        // Act
+        let matrix_in_column_major = original_matrix.get_column_major_value();
+
+        // Assert
+        assert!( matrix_in_column_major.iter().eq(expected_matrix.iter()) );
+    }
+
+    #[test]
+    fn should_calculate_magnitude_correctly() {
+        // Arrange
+        let my_vector = Vector4::new(2.0, 4.0, 7.0, 8.0);
+
+        // Act
+        let magnitude = my_vector.magnitude();
+        let normalized = my_vector.normalize();
+
+        // Assert
+        assert!(magnitude.approx_eq(&133.0f32.sqrt()));
+        assert!(normalized.magnitude().approx_eq(&1.0));
+        assert!((normalized * magnitude).approx_eq(&my_vector));
+    }
+
+    #[test]
+    fn should_add_and_subtract_component_wise_when_using_vector_operators() {
+        // Arrange
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(4.0, -5.0, 6.0);
+
+        // Act
+        let sum = a + b;
+        let difference = a - b;
+        let mut accumulated = a;
+        accumulated += b;
+        accumulated *= 2.0;
+
+        // Assert
+        assert_eq!((sum.x, sum.y, sum.z), (5.0, -3.0, 9.0));
+        assert_eq!((difference.x, difference.y, difference.z), (-3.0, 7.0, -3.0));
+        assert_eq!((accumulated.x, accumulated.y, accumulated.z), (10.0, -6.0, 18.0));
+    }
+
+    #[test]
+    fn should_return_perpendicular_vector_when_calculating_cross_product() {
+        // Arrange
+        let x_axis = Vector3::new(1.0, 0.0, 0.0);
+        let y_axis = Vector3::new(0.0, 1.0, 0.0);
+
+        // Act
+        let z_axis = x_axis.cross(&y_axis);
+
+        // Assert
+        assert_eq!((z_axis.x, z_axis.y, z_axis.z), (0.0, 0.0, 1.0));
+        assert_eq!(Vector2::new(1.0, 0.0).cross(&Vector2::new(0.0, 1.0)), 1.0);
+    }
+
+    #[test]
+    fn should_return_unit_length_vector_when_normalizing() {
+        // Arrange
+        let vector = Vector2::new(3.0, 4.0);
+
+        // Act
+        let normalized = vector.normalize();
+
+        // Assert
+        assert_eq!(vector.length(), 5.0);
+        assert_eq!(vector.length_squared(), 25.0);
+        assert!((normalized.length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_reflect_and_project_correctly() {
+        // Arrange
+        let velocity = Vector3::new(1.0, -1.0, 0.0);
+        let ground_normal = Vector3::new(0.0, 1.0, 0.0);
+
+        // Act
+        let reflected = velocity.reflect(&ground_normal);
+        let projected = velocity.project_on(&Vector3::new(2.0, 0.0, 0.0));
+
+        // Assert
+        assert_eq!((reflected.x, reflected.y, reflected.z), (1.0, 1.0, 0.0));
+        assert_eq!((projected.x, projected.y, projected.z), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn should_interpolate_and_clamp_component_wise() {
+        // Arrange
+        let a = Vector4::new(0.0, 10.0, -4.0, 1.0);
+        let b = Vector4::new(10.0, 20.0, 4.0, 1.0);
+
+        // Act
+        let halfway = a.lerp(&b, 0.5);
+        let clamped = Vector4::new(-1.0, 25.0, 0.0, 1.0).clamp(&a, &b);
+
+        // Assert
+        assert_eq!(halfway.as_array(), [5.0, 15.0, 0.0, 1.0]);
+        assert_eq!(clamped.as_array(), [0.0, 20.0, 0.0, 1.0]);
+        assert_eq!(a.distance(&b), (100.0f32 + 100.0 + 64.0).sqrt());
+    }
+
+    #[test]
+    fn should_calculate_determinant_correctly() {
+        // Arrange
+        let matrix = Mat4::new([
+            1.0, 8.0, 2.0, 3.0,
+            2.0, 1.0, 1.0, 1.0,
+            3.0, 2.0, 4.0, 3.0,
+            1.0, 9.0, 4.0, 3.0
+        ]);
+
+        // Act
+        let determinant = matrix.determinant();
+
+        // Assert
+        assert_eq!(determinant, 48.0);
+        assert_eq!(Mat4::scale(&Vector3::new(2.0, 3.0, 4.0)).determinant(), 24.0);
+    }
+
+    #[test]
+    fn should_return_identity_when_multiplying_matrix_with_its_inverse() {
+        // Arrange
+        let matrix = Mat4::new([
+            1.0, 8.0, 2.0, 3.0,
+            2.0, 1.0, 1.0, 1.0,
+            3.0, 2.0, 4.0, 3.0,
+            1.0, 9.0, 4.0, 3.0
+        ]);
+
+        // Act
+        let inverse = matrix.inverse().expect("Matrix should be invertible.");
+
+        // Assert
+        assert!(matrix.mul(&inverse).approx_eq(&Mat4::identity()));
+        assert!(inverse.mul(&matrix).approx_eq(&Mat4::identity()));
+    }
+
+    #[test]
+    fn should_return_none_when_inverting_singular_matrix() {
+        // Arrange
+        let matrix = Mat4::new([
+            1.0, 2.0, 3.0, 4.0,
+            2.0, 4.0, 6.0, 8.0,
+            3.0, 2.0, 4.0, 3.0,
+            1.0, 9.0, 4.0, 3.0
+        ]);
+
+        // Act & Assert
+        assert!(matrix.inverse().is_none());
+        assert!(Mat4::scale(&Vector3::new(1.0, 0.0, 1.0)).inverse_affine().is_none());
+    }
+
+    #[test]
+    fn should_match_general_inverse_when_using_affine_inverse() {
+        // Arrange
+        let matrix = Mat4::scale(&Vector3::new(2.0, 0.5, 3.0))
+            .mul(&Mat4::rotate_y(0.7))
+            .mul(&Mat4::rotate_x(-1.2))
+            .mul(&Mat4::translate(&Vector3::new(10.0, -4.0, 2.5)));
+
+        // Act
+        let affine_inverse = matrix.inverse_affine().expect("Matrix should be invertible.");
+        let general_inverse = matrix.inverse().expect("Matrix should be invertible.");
+
+        // Assert
+        assert!(affine_inverse.approx_eq(&general_inverse));
+        assert!(matrix.mul(&affine_inverse).approx_eq(&Mat4::identity()));
+    }
+
+    #[test]
+    fn should_rotate_vector_the_same_as_matrix_when_using_quaternion() {
+        // Arrange
+        let quat = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.8);
+        let matrix = Mat4::rotate_y(0.8);
+
+        // Act & Assert
+        assert!(quat.to_mat4().approx_eq(&matrix));
+        assert!(quat.rotate_vector(&Vector3::new(1.0, 0.0, 0.0)).approx_eq(&Vector3::new(0.8f32.cos(), 0.0, -0.8f32.sin())));
+    }
+
+    #[test]
+    fn should_compose_in_the_same_order_as_matrices() {
+        // Arrange
+        let first = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 0.5);
+        let second = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), -1.3);
+
+        // Act
+        let composed = first.mul(&second);
+
+        // Assert
+        assert!(composed.to_mat4().approx_eq(&first.to_mat4().mul(&second.to_mat4())));
+        assert!(Quaternion::from_euler(0.5, -1.3, 0.0).to_mat4().approx_eq(&Mat4::rotate_y(-1.3).mul(&Mat4::rotate_x(0.5))));
+    }
+
+    #[test]
+    fn should_return_identity_when_composing_quaternion_with_its_inverse() {
+        // Arrange
+        let quat = Quaternion::new(1.0, 2.0, -3.0, 0.5);
+
+        // Act
+        let result = quat.mul(&quat.inverse());
+
+        // Assert
+        assert!((result.w - 1.0).abs() < 1e-6 && result.x.abs() < 1e-6 && result.y.abs() < 1e-6 && result.z.abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_round_trip_when_converting_quaternion_to_and_from_matrix() {
+        // Arrange
+        let axes = [Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, -2.0, 0.5)];
+
+        for axis in axes.iter() {
+            for angle in [0.3f32, 2.0, 3.1].iter() {
+                let quat = Quaternion::from_axis_angle(axis, *angle);
+
+                // Act
+                let round_tripped = Quaternion::from_mat4(&quat.to_mat4());
+
+                // Assert
+                assert!(round_tripped.dot(&quat).abs() > 0.99999, "Expected {:?} but got {:?}", quat, round_tripped);
+            }
+        }
+    }
+
+    #[test]
+    fn should_interpolate_halfway_rotation_when_using_slerp() {
+        // Arrange
+        let start = Quaternion::identity();
+        let end = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 1.5);
+
+        // Act
+        let halfway = start.slerp(&end, 0.5);
+        let nlerp_halfway = start.nlerp(&end, 0.5);
+
+        // Assert
+        let expected = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), 0.75);
+        assert!(halfway.dot(&expected) > 0.99999);
+        assert!(nlerp_halfway.dot(&expected) > 0.99999);
+        assert!(start.slerp(&end, 1.0).dot(&end) > 0.99999);
+    }
+
+    #[test]
+    fn should_return_correct_view_matrix_when_using_left_handed_look_at() {
+        // Arrange
+        // A camera on the positive X axis looking at the origin. Its X axis points along world Z, and it looks down world -X.
+        let expected_matrix = Mat4::new([
+            0.0, 0.0, -1.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 3.0, 1.0
+        ]);
+
+        // Act
+        let view = Mat4::look_at_lh(&Vector3::new(3.0, 0.0, 0.0), &Vector3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0));
+
+        // Assert
+        assert!(view.approx_eq(&expected_matrix));
+    }
+
+    #[test]
+    fn should_return_correct_view_matrix_when_using_right_handed_look_at() {
+        // Arrange
+        // In a right-handed system the camera looks down its own -Z axis, so the origin ends up at z = -5.
+        let expected_matrix = Mat4::new([
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, -5.0, 1.0
+        ]);
+
+        // Act
+        let view = Mat4::look_at_rh(&Vector3::new(0.0, 0.0, 5.0), &Vector3::new(0.0, 0.0, 0.0), &Vector3::new(0.0, 1.0, 0.0));
+
+        // Assert
+        assert!(view.approx_eq(&expected_matrix));
+    }
+
+    #[test]
+    fn should_return_correct_matrix_when_creating_orthographic_projection() {
+        // Arrange
+        let expected_matrix = Mat4::new([
+            2.0 / 800.0, 0.0, 0.0, 0.0,
+            0.0, 2.0 / 600.0, 0.0, 0.0,
+            0.0, 0.0, 0.5, 0.0,
+            -1.0, -1.0, -0.5, 1.0
+        ]);
+
+        // Act
+        let projection = Mat4::orthographic(0.0, 800.0, 0.0, 600.0, 1.0, 3.0);
+
+        // Assert
+        assert!(projection.approx_eq(&expected_matrix));
+    }
+
+    #[test]
+    fn should_match_centered_projection_when_off_center_projection_is_symmetric() {
+        // Arrange
+        let fov = 45.0f32.to_radians();
+        let near = 0.1;
+        let top = near * (fov * 0.5).tan();
+        let right = top * (800.0 / 600.0);
+
+        // Act
+        let off_center = Mat4::projection_off_center(-right, right, -top, top, near, 100.0);
+
+        // Assert
+        assert!(off_center.approx_eq(&Mat4::projection(fov, 800.0, 600.0, near, 100.0)));
+    }
+
+    // Gives the depth buffer value of a point at view space depth z, by doing the perspective divide of the projected Z by W.
+    fn projected_depth(projection: &Mat4, z: f32) -> f32 {
+        (z * projection.get(2, 2) + projection.get(2, 3)) / (z * projection.get(3, 2) + projection.get(3, 3))
+    }
+
+    #[test]
+    fn should_map_near_to_one_and_far_to_zero_when_using_reversed_depth_range() {
+        // Arrange
+        let fov = 45.0f32.to_radians();
+
+        // Act
+        let standard = Mat4::projection_with_depth_range(fov, 800.0, 600.0, 0.1, 100.0, DepthRange::Standard);
+        let reversed = Mat4::projection_with_depth_range(fov, 800.0, 600.0, 0.1, 100.0, DepthRange::Reversed);
+
+        // Assert
+        assert!(standard.approx_eq(&Mat4::projection(fov, 800.0, 600.0, 0.1, 100.0)));
+        assert!((projected_depth(&reversed, 0.1) - 1.0).abs() < 1e-6);
+        assert!(projected_depth(&reversed, 100.0).abs() < 1e-6);
+        assert!(projected_depth(&reversed, 10.0) > projected_depth(&reversed, 20.0));
+    }
+
+    #[test]
+    fn should_approach_far_depth_at_infinity_when_using_infinite_projection() {
+        // Arrange
+        let fov = 45.0f32.to_radians();
+
+        // Act
+        let standard = Mat4::projection_infinite(fov, 800.0, 600.0, 0.1, DepthRange::Standard);
+        let reversed = Mat4::projection_infinite(fov, 800.0, 600.0, 0.1, DepthRange::Reversed);
+
+        // Assert
+        assert!(projected_depth(&standard, 0.1).abs() < 1e-6);
+        assert!((projected_depth(&standard, 1.0e7) - DepthRange::Standard.clear_depth()).abs() < 1e-6);
+        assert!((projected_depth(&reversed, 0.1) - 1.0).abs() < 1e-6);
+        assert!((projected_depth(&reversed, 1.0e7) - DepthRange::Reversed.clear_depth()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_return_identity_when_multiplying_mat3_with_its_inverse() {
+        // Arrange
+        let matrix = Mat3::new([
+            2.0, 0.0, 1.0,
+            1.0, 3.0, 0.0,
+            0.0, 1.0, 4.0
+        ]);
+
+        // Act
+        let inverse = matrix.inverse().expect("Matrix should be invertible.");
+        let product = matrix.mul(&inverse);
+
+        // Assert
+        assert_eq!(matrix.determinant(), 25.0);
+        for (a, e) in product.get_value().iter().zip(Mat3::identity().get_value().iter()) {
+            assert!((a - e).abs() < 1e-6);
+        }
+        assert!(Mat3::new([1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 0.0, 1.0, 0.0]).inverse().is_none());
+    }
+
+    #[test]
+    fn should_round_trip_when_converting_mat3_to_and_from_mat4() {
+        // Arrange
+        let rotation = Quaternion::from_axis_angle(&Vector3::new(1.0, 1.0, 0.0), 0.9);
+
+        // Act
+        let mat3 = Mat3::from_quaternion(&rotation);
+
+        // Assert
+        assert!(mat3.to_mat4().approx_eq(&rotation.to_mat4()));
+        assert_eq!(Mat3::from_mat4(&mat3.to_mat4()).get_value(), mat3.get_value());
+    }
+
+    #[test]
+    fn should_scale_inversely_when_creating_normal_matrix_from_non_uniform_scale() {
+        // Arrange
+        let world = Mat4::scale(&Vector3::new(2.0, 4.0, 1.0)).mul(&Mat4::translate(&Vector3::new(5.0, 0.0, 0.0)));
+
+        // Act
+        let normal_matrix = world.normal_matrix().expect("Matrix should be invertible.");
+
+        // Assert
+        assert_eq!(normal_matrix.get_value(), [0.5, 0.0, 0.0, 0.0, 0.25, 0.0, 0.0, 0.0, 1.0]);
+        assert!(Mat4::rotate_x(0.4).normal_matrix().unwrap().to_mat4().approx_eq(&Mat4::rotate_x(0.4)));
+    }
+
+    #[test]
+    fn should_pad_every_column_when_getting_padded_column_major_value() {
+        // Arrange
+        let matrix = Mat3::new([
+            11.0, 12.0, 13.0,
+            21.0, 22.0, 23.0,
+            31.0, 32.0, 33.0
+        ]);
+
+        let expected_value: [f32; 12] = [11.0, 21.0, 31.0, 0.0,
+                                         12.0, 22.0, 32.0, 0.0,
+                                         13.0, 23.0, 33.0, 0.0];
+
+        // Act
+        let value = matrix.get_padded_column_major_value();
+
+        // Assert
+        assert_eq!(value, expected_value);
+    }
+
+    #[test]
+    fn should_multiply_row_vector_with_matrix_columns() {
+        // Arrange
+        let matrix = Mat4::new([
+            1.0, 8.0, 2.0, 3.0,
+            2.0, 1.0, 1.0, 1.0,
+            3.0, 2.0, 4.0, 3.0,
+            1.0, 9.0, 4.0, 3.0
+        ]);
+
+        // Act
+        let result = Vector4::new(1.0, 2.0, 3.0, 4.0) * matrix;
+
+        // Assert
+        assert_eq!(result.as_array(), [18.0, 52.0, 32.0, 26.0]);
+    }
+
+    #[test]
+    fn should_apply_translation_to_points_but_not_directions() {
+        // Arrange
+        let matrix = Mat4::rotate_y(90.0f32.to_radians()).mul(&Mat4::translate(&Vector3::new(0.0, 10.0, 0.0)));
+
+        // Act
+        let point = matrix.transform_point(&Vector3::new(1.0, 0.0, 0.0));
+        let direction = matrix.transform_direction(&Vector3::new(1.0, 0.0, 0.0));
+
+        // Assert
+        assert!(point.approx_eq(&Vector3::new(0.0, 10.0, -1.0)));
+        assert!(direction.approx_eq(&Vector3::new(0.0, 0.0, -1.0)));
+    }
+
+    #[test]
+    fn should_do_perspective_divide_when_transforming_point_with_projection() {
+        // Arrange
+        let projection = Mat4::projection(90.0f32.to_radians(), 800.0, 800.0, 1.0, 100.0);
+
+        // Act
+        let on_near_plane = projection.transform_point(&Vector3::new(1.0, -1.0, 1.0));
+        let on_far_plane = projection.transform_point(&Vector3::new(0.0, 50.0, 100.0));
+
+        // Assert
+        assert!(on_near_plane.approx_eq(&Vector3::new(1.0, -1.0, 0.0)));
+        assert!(on_far_plane.approx_eq(&Vector3::new(0.0, 0.5, 1.0)));
+    }
+
+    #[test]
+    fn should_transform_every_element_when_using_batch_transforms() {
+        // Arrange
+        let matrix = Mat4::scale(&Vector3::new(2.0, 2.0, 2.0)).mul(&Mat4::translate(&Vector3::new(1.0, 0.0, 0.0)));
+        let vectors = [Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)];
+
+        // Act
+        let points = matrix.transform_points(&vectors);
+        let directions = matrix.transform_directions(&vectors);
+
+        // Assert
+        assert_eq!(points.len(), 3);
+        assert!(points[0].approx_eq(&Vector3::new(3.0, 0.0, 0.0)));
+        assert!(points[2].approx_eq(&Vector3::new(1.0, 0.0, 2.0)));
+        assert!(directions[1].approx_eq(&Vector3::new(0.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn should_keep_millimetre_precision_at_orbital_distances_when_using_double_precision() {
+        // Arrange
+        let orbit: f64 = 6_771_000.0;
+        let millimetre: f64 = 0.001;
+
+        // Act
+        let single = Vector3::new(orbit as f32, 0.0, 0.0) + Vector3::new(millimetre as f32, 0.0, 0.0);
+        let double = DVector3::new(orbit, 0.0, 0.0) + DVector3::new(millimetre, 0.0, 0.0);
+
+        // Assert
+        assert_eq!(single.x, orbit as f32);
+        assert!((double.x - orbit - millimetre).abs() < 1e-6);
+    }
+
+    #[test]
+    fn should_match_single_precision_when_using_double_precision_matrices() {
+        // Arrange
+        let single = Mat4::rotate_y(0.6).mul(&Mat4::translate(&Vector3::new(1.0, 2.0, 3.0)));
+        let double = DMat4::rotate_y(0.6).mul(&DMat4::translate(&DVector3::new(1.0, 2.0, 3.0)));
+
+        // Act
+        let inverse = double.inverse().expect("Matrix should be invertible.");
+
+        // Assert
+        assert!(double.cast::<f32>().approx_eq(&single));
+        assert!(inverse.cast::<f32>().approx_eq(&single.inverse().unwrap()));
+    }
+
+    #[test]
+    fn should_convert_between_precisions() {
+        // Arrange
+        let vector = Vector3::new(0.1, -2.5, 7.0);
+        let quaternion = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.3);
+
+        // Act
+        let widened: DVector3 = vector.into();
+        let narrowed: Vector3 = widened.cast();
+        let widened_quaternion: DQuaternion = quaternion.into();
+
+        // Assert
+        assert_eq!(widened.x, 0.1f32 as f64);
+        assert_eq!((narrowed.x, narrowed.y, narrowed.z), (vector.x, vector.y, vector.z));
+        assert_eq!(widened_quaternion.w, quaternion.w as f64);
+        assert!((DVector3::new(0.1, 0.2, 0.3).cast::<f32>().x - 0.1).abs() < 1e-7);
+    }
+
+    #[test]
+    fn should_rotate_counter_clockwise_when_using_vector2_rotations() {
+        // Arrange
+        let right = Vector2::new(1.0, 0.0);
+        let up = Vector2::new(0.0, 1.0);
+
+        // Act
+        let rotated = right.rotate(std::f32::consts::FRAC_PI_2);
+
+        // Assert
+        assert!(rotated.approx_eq(&up));
+        assert_eq!(right.perp(), up);
+        assert!(up.angle().approx_eq(&std::f32::consts::FRAC_PI_2));
+        assert!(up.angle_to(&right).approx_eq(&-std::f32::consts::FRAC_PI_2));
+        assert_eq!(right.cross(&up), 1.0);
+    }
+}
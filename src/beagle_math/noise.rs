@@ -0,0 +1,561 @@
+use crate::beagle_math::{Random, Vector2, Vector3};
+
+// The 12 gradient directions of improved Perlin noise and simplex noise: the midpoints of the edges of a cube.
+const GRADIENTS_3D: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0], [-1.0, 1.0, 0.0], [1.0, -1.0, 0.0], [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0], [-1.0, 0.0, 1.0], [1.0, 0.0, -1.0], [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0], [0.0, -1.0, 1.0], [0.0, 1.0, -1.0], [0.0, -1.0, -1.0]
+];
+
+// Skew factors for 2D simplex noise, (sqrt(3) - 1) / 2 and (3 - sqrt(3)) / 6, written out so they are the same on every platform.
+const SKEW_2D: f32 = 0.366_025_42;
+const UNSKEW_2D: f32 = 0.211_324_87;
+
+const SKEW_3D: f32 = 1.0 / 3.0;
+const UNSKEW_3D: f32 = 1.0 / 6.0;
+
+// A shuffled table of the numbers 0 to 255, used to hash lattice coordinates into pseudo-random gradients and values.
+// The table is repeated once, so two lookups can be chained without wrapping the index.
+#[derive(Clone)]
+pub struct PermutationTable {
+    values: [u8; 512]
+}
+
+impl PermutationTable {
+    pub fn new(seed: u64) -> PermutationTable {
+        let mut permutation: Vec<u8> = (0..=255).collect();
+        Random::new(seed).shuffle(&mut permutation);
+
+        let mut values = [0; 512];
+
+        for (index, value) in values.iter_mut().enumerate() {
+            *value = permutation[index & 255];
+        }
+
+        PermutationTable {
+            values
+        }
+    }
+
+    // Only the lowest 8 bits of the coordinates are used. Huge coordinates saturate when the cell is converted to i32,
+    // so the neighbouring cells are found with wrapping_add, which cannot overflow and keeps the lowest bits correct.
+    fn hash1(&self, x: i32) -> usize {
+        self.values[(x & 255) as usize] as usize
+    }
+
+    fn hash2(&self, x: i32, y: i32) -> usize {
+        self.values[self.hash1(x) + (y & 255) as usize] as usize
+    }
+
+    fn hash3(&self, x: i32, y: i32, z: i32) -> usize {
+        self.values[self.hash2(x, y) + (z & 255) as usize] as usize
+    }
+}
+
+// The noise function each octave of a fractal is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseBasis {
+    Perlin,
+    Simplex,
+    Value
+}
+
+// Settings for summing several octaves of noise into a fractal.
+// Each octave has its frequency multiplied by the lacunarity and its amplitude multiplied by the gain,
+// adding finer and finer detail, like boulders on top of hills on top of crater rims.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fractal {
+    pub basis: NoiseBasis,
+    pub octaves: u32,
+    pub frequency: f32,
+    pub lacunarity: f32,
+    pub gain: f32
+}
+
+impl Default for Fractal {
+    fn default() -> Fractal {
+        Fractal {
+            basis: NoiseBasis::Perlin,
+            octaves: 5,
+            frequency: 1.0,
+            lacunarity: 2.0,
+            gain: 0.5
+        }
+    }
+}
+
+// Coherent noise, which gives random values that change smoothly with the position, making it the basis of procedural terrain.
+// All the functions return values in about [-1, 1], apart from "ridged", which returns values in [0, 1].
+// Noises with the same seed give exactly the same values on every platform, as only exact operations such as +, -, *, / and floor are used.
+#[derive(Clone)]
+pub struct Noise {
+    permutation: PermutationTable
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Noise {
+        Noise {
+            permutation: PermutationTable::new(seed)
+        }
+    }
+
+    // Improved Perlin noise (https://mrl.cs.nyu.edu/~perlin/paper445.pdf).
+    // The noise is zero at every integer coordinate, and interpolates between random gradients in between.
+    pub fn perlin_1d(&self, x: f32) -> f32 {
+        let x0 = x.floor();
+        let cell = x0 as i32;
+        let x = x - x0;
+
+        // Scaled so the noise stays within [-1, 1].
+        let gradient = |offset: i32| gradient_1d(self.permutation.hash1(cell.wrapping_add(offset))) / 4.0;
+
+        lerp(gradient(0) * x, gradient(1) * (x - 1.0), fade(x))
+    }
+
+    pub fn perlin_2d(&self, point: &Vector2) -> f32 {
+        let (x0, y0) = (point.x.floor(), point.y.floor());
+        let (cell_x, cell_y) = (x0 as i32, y0 as i32);
+        let (x, y) = (point.x - x0, point.y - y0);
+
+        let corner = |offset_x: i32, offset_y: i32| {
+            let gradient = GRADIENTS_3D[self.permutation.hash2(cell_x.wrapping_add(offset_x), cell_y.wrapping_add(offset_y)) % 12];
+            gradient[0] * (x - offset_x as f32) + gradient[1] * (y - offset_y as f32)
+        };
+
+        let (u, v) = (fade(x), fade(y));
+
+        lerp(lerp(corner(0, 0), corner(1, 0), u), lerp(corner(0, 1), corner(1, 1), u), v)
+    }
+
+    pub fn perlin_3d(&self, point: &Vector3) -> f32 {
+        let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (cell_x, cell_y, cell_z) = (x0 as i32, y0 as i32, z0 as i32);
+        let (x, y, z) = (point.x - x0, point.y - y0, point.z - z0);
+
+        let corner = |offset_x: i32, offset_y: i32, offset_z: i32| {
+            let gradient = GRADIENTS_3D[self.permutation.hash3(cell_x.wrapping_add(offset_x), cell_y.wrapping_add(offset_y), cell_z.wrapping_add(offset_z)) % 12];
+            gradient[0] * (x - offset_x as f32) + gradient[1] * (y - offset_y as f32) + gradient[2] * (z - offset_z as f32)
+        };
+
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let near = lerp(lerp(corner(0, 0, 0), corner(1, 0, 0), u), lerp(corner(0, 1, 0), corner(1, 1, 0), u), v);
+        let far = lerp(lerp(corner(0, 0, 1), corner(1, 0, 1), u), lerp(corner(0, 1, 1), corner(1, 1, 1), u), v);
+
+        lerp(near, far, w)
+    }
+
+    // Simplex noise (https://weber.itn.liu.se/~stegu/simplexnoise/simplexnoise.pdf).
+    // Works on a grid of triangles instead of squares, which needs fewer corners and has no visible grid directions.
+    // In 1D the "simplices" are just the cells, but the radial falloff still gives it a different look from Perlin noise.
+    pub fn simplex_1d(&self, x: f32) -> f32 {
+        let x0 = x.floor();
+        let cell = x0 as i32;
+        let mut total = 0.0;
+
+        for offset in 0..2 {
+            let x = x - x0 - offset as f32;
+            let falloff = 1.0 - x * x;
+            let falloff_squared = falloff * falloff;
+
+            total += falloff_squared * falloff_squared * gradient_1d(self.permutation.hash1(cell.wrapping_add(offset))) * x;
+        }
+
+        // Scales the result to about [-1, 1].
+        0.395 * total
+    }
+
+    pub fn simplex_2d(&self, point: &Vector2) -> f32 {
+        // Skew the input space to find the triangle the point is in.
+        let skew = (point.x + point.y) * SKEW_2D;
+        let (i, j) = ((point.x + skew).floor(), (point.y + skew).floor());
+        let unskew = (i + j) * UNSKEW_2D;
+
+        let x0 = point.x - (i - unskew);
+        let y0 = point.y - (j - unskew);
+
+        // The middle corner depends on which of the two triangles of the cell the point is in.
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + UNSKEW_2D, y0 - j1 as f32 + UNSKEW_2D),
+            (1, 1, x0 - 1.0 + 2.0 * UNSKEW_2D, y0 - 1.0 + 2.0 * UNSKEW_2D)
+        ];
+
+        let (cell_x, cell_y) = (i as i32, j as i32);
+        let mut total = 0.0;
+
+        for (offset_x, offset_y, x, y) in corners.iter() {
+            let falloff = 0.5 - x * x - y * y;
+
+            if falloff > 0.0 {
+                let gradient = GRADIENTS_3D[self.permutation.hash2(cell_x.wrapping_add(*offset_x), cell_y.wrapping_add(*offset_y)) % 12];
+                let falloff_squared = falloff * falloff;
+                total += falloff_squared * falloff_squared * (gradient[0] * x + gradient[1] * y);
+            }
+        }
+
+        // Scales the result to about [-1, 1].
+        70.0 * total
+    }
+
+    pub fn simplex_3d(&self, point: &Vector3) -> f32 {
+        let skew = (point.x + point.y + point.z) * SKEW_3D;
+        let (i, j, k) = ((point.x + skew).floor(), (point.y + skew).floor(), (point.z + skew).floor());
+        let unskew = (i + j + k) * UNSKEW_3D;
+
+        let x0 = point.x - (i - unskew);
+        let y0 = point.y - (j - unskew);
+        let z0 = point.z - (k - unskew);
+
+        // The cube is split into six tetrahedra, picked by the order of the coordinates.
+        let ((i1, j1, k1), (i2, j2, k2)) = if x0 >= y0 {
+            if y0 >= z0 {
+                ((1, 0, 0), (1, 1, 0))
+            } else if x0 >= z0 {
+                ((1, 0, 0), (1, 0, 1))
+            } else {
+                ((0, 0, 1), (1, 0, 1))
+            }
+        } else if y0 < z0 {
+            ((0, 0, 1), (0, 1, 1))
+        } else if x0 < z0 {
+            ((0, 1, 0), (0, 1, 1))
+        } else {
+            ((0, 1, 0), (1, 1, 0))
+        };
+
+        let corners = [
+            (0, 0, 0, x0, y0, z0),
+            (i1, j1, k1, x0 - i1 as f32 + UNSKEW_3D, y0 - j1 as f32 + UNSKEW_3D, z0 - k1 as f32 + UNSKEW_3D),
+            (i2, j2, k2, x0 - i2 as f32 + 2.0 * UNSKEW_3D, y0 - j2 as f32 + 2.0 * UNSKEW_3D, z0 - k2 as f32 + 2.0 * UNSKEW_3D),
+            (1, 1, 1, x0 - 1.0 + 3.0 * UNSKEW_3D, y0 - 1.0 + 3.0 * UNSKEW_3D, z0 - 1.0 + 3.0 * UNSKEW_3D)
+        ];
+
+        let (cell_x, cell_y, cell_z) = (i as i32, j as i32, k as i32);
+        let mut total = 0.0;
+
+        for (offset_x, offset_y, offset_z, x, y, z) in corners.iter() {
+            let falloff = 0.6 - x * x - y * y - z * z;
+
+            if falloff > 0.0 {
+                let gradient = GRADIENTS_3D[self.permutation.hash3(cell_x.wrapping_add(*offset_x), cell_y.wrapping_add(*offset_y), cell_z.wrapping_add(*offset_z)) % 12];
+                let falloff_squared = falloff * falloff;
+                total += falloff_squared * falloff_squared * (gradient[0] * x + gradient[1] * y + gradient[2] * z);
+            }
+        }
+
+        32.0 * total
+    }
+
+    // Value noise interpolates between random values at the integer coordinates, instead of random gradients.
+    // It is cheaper than Perlin noise, but blockier.
+    pub fn value_1d(&self, x: f32) -> f32 {
+        let x0 = x.floor();
+        let cell = x0 as i32;
+
+        let corner = |offset: i32| self.permutation.hash1(cell.wrapping_add(offset)) as f32 / 127.5 - 1.0;
+
+        lerp(corner(0), corner(1), fade(x - x0))
+    }
+
+    pub fn value_2d(&self, point: &Vector2) -> f32 {
+        let (x0, y0) = (point.x.floor(), point.y.floor());
+        let (cell_x, cell_y) = (x0 as i32, y0 as i32);
+        let (u, v) = (fade(point.x - x0), fade(point.y - y0));
+
+        let corner = |offset_x: i32, offset_y: i32| {
+            self.permutation.hash2(cell_x.wrapping_add(offset_x), cell_y.wrapping_add(offset_y)) as f32 / 127.5 - 1.0
+        };
+
+        lerp(lerp(corner(0, 0), corner(1, 0), u), lerp(corner(0, 1), corner(1, 1), u), v)
+    }
+
+    pub fn value_3d(&self, point: &Vector3) -> f32 {
+        let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (cell_x, cell_y, cell_z) = (x0 as i32, y0 as i32, z0 as i32);
+        let (u, v, w) = (fade(point.x - x0), fade(point.y - y0), fade(point.z - z0));
+
+        let corner = |offset_x: i32, offset_y: i32, offset_z: i32| {
+            self.permutation.hash3(cell_x.wrapping_add(offset_x), cell_y.wrapping_add(offset_y), cell_z.wrapping_add(offset_z)) as f32 / 127.5 - 1.0
+        };
+
+        let near = lerp(lerp(corner(0, 0, 0), corner(1, 0, 0), u), lerp(corner(0, 1, 0), corner(1, 1, 0), u), v);
+        let far = lerp(lerp(corner(0, 0, 1), corner(1, 0, 1), u), lerp(corner(0, 1, 1), corner(1, 1, 1), u), v);
+
+        lerp(near, far, w)
+    }
+
+    pub fn sample_1d(&self, basis: NoiseBasis, x: f32) -> f32 {
+        match basis {
+            NoiseBasis::Perlin => self.perlin_1d(x),
+            NoiseBasis::Simplex => self.simplex_1d(x),
+            NoiseBasis::Value => self.value_1d(x)
+        }
+    }
+
+    pub fn sample_2d(&self, basis: NoiseBasis, point: &Vector2) -> f32 {
+        match basis {
+            NoiseBasis::Perlin => self.perlin_2d(point),
+            NoiseBasis::Simplex => self.simplex_2d(point),
+            NoiseBasis::Value => self.value_2d(point)
+        }
+    }
+
+    pub fn sample_3d(&self, basis: NoiseBasis, point: &Vector3) -> f32 {
+        match basis {
+            NoiseBasis::Perlin => self.perlin_3d(point),
+            NoiseBasis::Simplex => self.simplex_3d(point),
+            NoiseBasis::Value => self.value_3d(point)
+        }
+    }
+
+    // Fractal Brownian motion: the sum of the octaves, divided by the sum of their amplitudes to stay within [-1, 1].
+    // Gives rolling hills and the general roughness of the surface.
+    pub fn fbm_1d(&self, x: f32, fractal: &Fractal) -> f32 {
+        fractal_sum(fractal, |frequency| self.sample_1d(fractal.basis, x * frequency))
+    }
+
+    pub fn fbm_2d(&self, point: &Vector2, fractal: &Fractal) -> f32 {
+        fractal_sum(fractal, |frequency| self.sample_2d(fractal.basis, &(*point * frequency)))
+    }
+
+    pub fn fbm_3d(&self, point: &Vector3, fractal: &Fractal) -> f32 {
+        fractal_sum(fractal, |frequency| self.sample_3d(fractal.basis, &(*point * frequency)))
+    }
+
+    // Ridged multifractal (from "Texturing and Modeling: A Procedural Approach" by Musgrave).
+    // Folding the noise with 1 - |n| turns its zero crossings into sharp ridges, like crater rims and mountain crests.
+    // Each octave is weighted by the previous one, so the detail gathers on the ridges while the valleys stay smooth.
+    pub fn ridged_1d(&self, x: f32, fractal: &Fractal) -> f32 {
+        ridged_sum(fractal, |frequency| self.sample_1d(fractal.basis, x * frequency))
+    }
+
+    pub fn ridged_2d(&self, point: &Vector2, fractal: &Fractal) -> f32 {
+        ridged_sum(fractal, |frequency| self.sample_2d(fractal.basis, &(*point * frequency)))
+    }
+
+    pub fn ridged_3d(&self, point: &Vector3, fractal: &Fractal) -> f32 {
+        ridged_sum(fractal, |frequency| self.sample_3d(fractal.basis, &(*point * frequency)))
+    }
+
+    // Domain warping (https://iquilezles.org/articles/warp) offsets the point by two other fBm lookups before sampling.
+    // This bends the features into flowing shapes, such as lava flows and the soft edges of old, eroded craters.
+    // A strength of zero gives plain fBm.
+    pub fn warped_fbm_1d(&self, x: f32, fractal: &Fractal, strength: f32) -> f32 {
+        self.fbm_1d(x + self.fbm_1d(x + 5.2, fractal) * strength, fractal)
+    }
+
+    pub fn warped_fbm_2d(&self, point: &Vector2, fractal: &Fractal, strength: f32) -> f32 {
+        // Two far apart offsets, so the lookups for the X and Y offsets are unrelated.
+        let offset = Vector2::new(
+            self.fbm_2d(point, fractal),
+            self.fbm_2d(&(*point + Vector2::new(5.2, 1.3)), fractal));
+
+        self.fbm_2d(&(*point + offset * strength), fractal)
+    }
+
+    pub fn warped_fbm_3d(&self, point: &Vector3, fractal: &Fractal, strength: f32) -> f32 {
+        let offset = Vector3::new(
+            self.fbm_3d(point, fractal),
+            self.fbm_3d(&(*point + Vector3::new(5.2, 1.3, 2.8)), fractal),
+            self.fbm_3d(&(*point + Vector3::new(1.7, 9.2, 4.1)), fractal));
+
+        self.fbm_3d(&(*point + offset * strength), fractal)
+    }
+}
+
+fn fractal_sum<F: Fn(f32) -> f32>(fractal: &Fractal, sample: F) -> f32 {
+    let mut frequency = fractal.frequency;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut total_amplitude = 0.0;
+
+    for _ in 0..fractal.octaves {
+        total += sample(frequency) * amplitude;
+        total_amplitude += amplitude;
+
+        frequency *= fractal.lacunarity;
+        amplitude *= fractal.gain;
+    }
+
+    if total_amplitude > 0.0 { total / total_amplitude } else { 0.0 }
+}
+
+fn ridged_sum<F: Fn(f32) -> f32>(fractal: &Fractal, sample: F) -> f32 {
+    let mut frequency = fractal.frequency;
+    let mut amplitude = 1.0;
+    let mut weight = 1.0;
+    let mut total = 0.0;
+    let mut total_amplitude = 0.0;
+
+    for _ in 0..fractal.octaves {
+        let ridge = 1.0 - sample(frequency).abs().min(1.0);
+        let signal = ridge * ridge * weight;
+
+        total += signal * amplitude;
+        total_amplitude += amplitude;
+
+        weight = signal.clamp(0.0, 1.0);
+        frequency *= fractal.lacunarity;
+        amplitude *= fractal.gain;
+    }
+
+    if total_amplitude > 0.0 { total / total_amplitude } else { 0.0 }
+}
+
+// One of the gradients -8..8 without 0, picked by the lowest 4 bits of the hash.
+fn gradient_1d(hash: usize) -> f32 {
+    let gradient = (hash & 7) as f32 + 1.0;
+
+    if hash & 8 == 0 { gradient } else { -gradient }
+}
+
+// The quintic curve 6t^5 - 15t^4 + 10t^3, which has zero first and second derivatives at 0 and 1,
+// so the noise has no visible creases at the cell borders.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    fn sample_points() -> Vec<Vector3> {
+        let mut random = Random::new(99);
+        (0..2000).map(|_| Vector3::new(random.range_f32(-50.0, 50.0), random.range_f32(-50.0, 50.0), random.range_f32(-50.0, 50.0))).collect()
+    }
+
+    #[test]
+    fn should_give_same_values_for_same_seed() {
+        // Arrange
+        let first = Noise::new(2024);
+        let second = Noise::new(2024);
+        let other = Noise::new(2025);
+        let point = Vector3::new(3.7, -1.2, 8.9);
+
+        // Assert
+        assert_eq!(first.perlin_3d(&point), second.perlin_3d(&point));
+        assert_eq!(first.simplex_3d(&point), second.simplex_3d(&point));
+        assert_ne!(first.perlin_3d(&point), other.perlin_3d(&point));
+    }
+
+    #[test]
+    fn should_be_zero_at_integer_coordinates_when_using_perlin_noise() {
+        // Arrange
+        let noise = Noise::new(1);
+
+        // Assert
+        assert_eq!(noise.perlin_1d(4.0), 0.0);
+        assert_eq!(noise.perlin_2d(&Vector2::new(-3.0, 7.0)), 0.0);
+        assert_eq!(noise.perlin_3d(&Vector3::new(2.0, 0.0, -9.0)), 0.0);
+    }
+
+    #[test]
+    fn should_stay_within_range_for_every_basis() {
+        // Arrange
+        let noise = Noise::new(5);
+
+        for point in sample_points() {
+            let point_2d = Vector2::new(point.x, point.y);
+
+            // Act
+            let values = [
+                noise.perlin_1d(point.x),
+                noise.perlin_2d(&point_2d),
+                noise.perlin_3d(&point),
+                noise.simplex_1d(point.x),
+                noise.value_1d(point.x),
+                noise.simplex_2d(&point_2d),
+                noise.simplex_3d(&point),
+                noise.value_2d(&point_2d),
+                noise.value_3d(&point)
+            ];
+
+            // Assert
+            for value in values.iter() {
+                assert!(value.abs() <= 1.05, "{} at {}", value, point);
+            }
+        }
+    }
+
+    #[test]
+    fn should_change_smoothly_with_position() {
+        // Arrange
+        let noise = Noise::new(8);
+        let step = Vector3::new(0.001, 0.0, 0.0);
+
+        for point in sample_points().iter().take(200) {
+            // Act
+            let perlin_change = (noise.perlin_3d(&(*point + step)) - noise.perlin_3d(point)).abs();
+            let simplex_change = (noise.simplex_3d(&(*point + step)) - noise.simplex_3d(point)).abs();
+
+            // Assert
+            assert!(perlin_change < 0.01 && simplex_change < 0.02);
+        }
+    }
+
+    #[test]
+    fn should_keep_fractals_within_range() {
+        // Arrange
+        let noise = Noise::new(13);
+        let fractal = Fractal { basis: NoiseBasis::Simplex, ..Fractal::default() };
+
+        for point in sample_points().iter().take(500) {
+            let point_2d = Vector2::new(point.x, point.z);
+
+            // Act
+            let fbm = noise.fbm_2d(&point_2d, &fractal);
+            let ridged = noise.ridged_3d(point, &fractal);
+            let warped = noise.warped_fbm_2d(&point_2d, &fractal, 4.0);
+            let profile = [noise.fbm_1d(point.y, &fractal), noise.warped_fbm_1d(point.y, &fractal, 4.0)];
+            let ridged_profile = noise.ridged_1d(point.y, &fractal);
+
+            // Assert
+            assert!(fbm.abs() <= 1.05 && warped.abs() <= 1.05);
+            assert!(profile.iter().all(|value| value.abs() <= 1.05));
+            assert!((0.0..=1.0).contains(&ridged) && (0.0..=1.0).contains(&ridged_profile));
+        }
+
+        assert_eq!(noise.warped_fbm_1d(0.3, &fractal, 0.0), noise.fbm_1d(0.3, &fractal));
+
+        let point = Vector2::new(0.3, 0.7);
+        assert_eq!(noise.warped_fbm_2d(&point, &fractal, 0.0), noise.fbm_2d(&point, &fractal));
+    }
+
+    #[test]
+    fn should_not_panic_when_coordinates_are_huge_or_infinite() {
+        // Arrange
+        let noise = Noise::new(1);
+        let huge = Vector3::new(3.0e9, -3.0e9, 1.0e30);
+        let infinite = Vector3::new(f32::INFINITY, f32::NEG_INFINITY, 0.0);
+
+        for (point, is_finite) in [(huge, true), (infinite, false)].iter() {
+            let point_2d = Vector2::new(point.x, point.y);
+
+            // Act
+            let values = [
+                noise.perlin_1d(point.x),
+                noise.perlin_2d(&point_2d),
+                noise.perlin_3d(point),
+                noise.simplex_1d(point.x),
+                noise.value_1d(point.x),
+                noise.simplex_2d(&point_2d),
+                noise.simplex_3d(point),
+                noise.value_2d(&point_2d),
+                noise.value_3d(point)
+            ];
+
+            // Assert
+            // Infinite coordinates have no position within their cell, and give NaN.
+            for value in values.iter() {
+                assert!(value.abs() <= 1.05 || (!is_finite && value.is_nan()), "{} at {}", value, point);
+            }
+        }
+    }
+}
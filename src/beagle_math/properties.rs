@@ -0,0 +1,108 @@
+// Property-based tests, checking that the identities the rest of the game relies on hold for randomly generated inputs,
+// instead of only for the handful of values picked in the unit tests.
+// proptest shrinks a failing input to the simplest case it can find, which makes the failures much easier to understand.
+
+use proptest::prelude::*;
+
+use crate::beagle_math::*;
+
+// Tolerance for results of a chain of f32 operations, such as an inverse followed by a multiplication.
+const EPSILON: f32 = 1e-3;
+
+fn axis() -> impl Strategy<Value = Vector3> {
+    (-1.0f32..1.0, -1.0f32..1.0, -1.0f32..1.0)
+        .prop_filter("axis must not be zero", |(x, y, z)| Vector3::new(*x, *y, *z).length() > 0.1)
+        .prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+
+fn angle() -> impl Strategy<Value = f32> {
+    -std::f32::consts::PI..std::f32::consts::PI
+}
+
+fn rotation() -> impl Strategy<Value = Quaternion> {
+    (axis(), angle()).prop_map(|(axis, angle)| Quaternion::from_axis_angle(&axis, angle))
+}
+
+// Any matrix with elements in [-1, 1] and 4 added to its diagonal is strictly diagonally dominant,
+// which guarantees it is invertible and well-conditioned enough for f32.
+fn invertible_matrix() -> impl Strategy<Value = Mat4> {
+    prop::array::uniform16(-1.0f32..1.0).prop_map(|mut elements| {
+        for diagonal in [0, 5, 10, 15].iter() {
+            elements[*diagonal] += 4.0;
+        }
+
+        Mat4::new(elements)
+    })
+}
+
+fn any_matrix() -> impl Strategy<Value = Mat4> {
+    prop::array::uniform16(-1000.0f32..1000.0).prop_map(Mat4::new)
+}
+
+proptest! {
+    #[test]
+    fn multiplying_with_inverse_gives_identity(matrix in invertible_matrix()) {
+        let inverse = matrix.inverse().expect("Diagonally dominant matrices are invertible.");
+
+        prop_assert!(matrix.mul(&inverse).approx_eq_eps(&Mat4::identity(), EPSILON));
+        prop_assert!(inverse.mul(&matrix).approx_eq_eps(&Mat4::identity(), EPSILON));
+    }
+
+    #[test]
+    fn rotation_matrices_are_orthonormal(rotation in rotation()) {
+        let matrix = Mat3::from_quaternion(&rotation);
+
+        prop_assert!(matrix.mul(&matrix.transpose()).approx_eq_eps(&Mat3::identity(), EPSILON));
+        prop_assert!(matrix.determinant().approx_eq_eps(&1.0, EPSILON));
+    }
+
+    #[test]
+    fn axis_rotation_matrices_are_orthonormal(angle in angle()) {
+        for matrix in [Mat4::rotate_x(angle), Mat4::rotate_y(angle), Mat4::rotate_z(angle)].iter() {
+            let mut transposed = *matrix;
+            transposed.tranpose();
+
+            prop_assert!(matrix.mul(&transposed).approx_eq_eps(&Mat4::identity(), EPSILON));
+            prop_assert!(matrix.determinant().approx_eq_eps(&1.0, EPSILON));
+        }
+    }
+
+    #[test]
+    fn quaternion_survives_round_trip_through_matrix(rotation in rotation()) {
+        let round_trip = Quaternion::from_mat4(&rotation.to_mat4());
+
+        // q and -q are the same rotation, so either is a correct result.
+        let negated = Quaternion::new(-round_trip.x, -round_trip.y, -round_trip.z, -round_trip.w);
+
+        prop_assert!(round_trip.approx_eq_eps(&rotation, EPSILON) || negated.approx_eq_eps(&rotation, EPSILON),
+            "{} became {}", rotation, round_trip);
+    }
+
+    #[test]
+    fn transposing_twice_gives_original_matrix(matrix in any_matrix()) {
+        let mut transposed = matrix;
+        transposed.tranpose();
+        transposed.tranpose();
+
+        prop_assert_eq!(transposed, matrix);
+    }
+
+    #[test]
+    fn projection_maps_near_to_zero_and_far_to_one(near in 0.01f32..10.0, depth in 1.0f32..1000.0, fov in 0.2f32..2.5) {
+        let far = near + depth;
+        let projection = Mat4::projection(fov, 800.0, 600.0, near, far);
+
+        let near_depth = projection.transform_point(&Vector3::new(0.0, 0.0, near)).z;
+        let far_depth = projection.transform_point(&Vector3::new(0.0, 0.0, far)).z;
+
+        prop_assert!(near_depth.approx_eq_eps(&0.0, EPSILON), "near plane projected to {}", near_depth);
+        prop_assert!(far_depth.approx_eq_eps(&1.0, EPSILON), "far plane projected to {}", far_depth);
+    }
+
+    #[test]
+    fn quaternion_rotation_matches_matrix_rotation(rotation in rotation(), x in -100.0f32..100.0, y in -100.0f32..100.0, z in -100.0f32..100.0) {
+        let vector = Vector3::new(x, y, z);
+
+        prop_assert!(rotation.rotate_vector(&vector).approx_eq_eps(&rotation.to_mat4().transform_direction(&vector), EPSILON));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::beagle_math::{Quaternion, Vector2, Vector3};
+
+const MULTIPLIER: u64 = 6364136223846793005;
+
+// The stream used by Random::new. Any odd increment gives a different, independent sequence for the same seed.
+const DEFAULT_STREAM: u64 = 0xda3e39cb94b95bdb;
+
+// A seedable pseudo-random number generator, using PCG32 (https://www.pcg-random.org).
+// The same seed gives exactly the same numbers on every platform, so terrain, particle effects and replays can be reproduced.
+// To keep that promise, the helpers only use integer math and the floating point operations IEEE 754 requires to be exact
+// (+, -, *, / and sqrt). Functions like sin and cos can differ in the last bits between platforms, so directions and rotations
+// are picked by rejection sampling instead of from random angles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+    increment: u64
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    // Generators with the same seed but different streams give unrelated sequences,
+    // such as one stream for the terrain and another for the particles.
+    pub fn with_stream(seed: u64, stream: u64) -> Random {
+        let mut random = Random {
+            state: 0,
+            increment: (stream << 1) | 1
+        };
+
+        random.next_u32();
+        random.state = random.state.wrapping_add(seed);
+        random.next_u32();
+
+        random
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+
+        // The output is the high bits of the old state, xor-shifted and rotated by its top 5 bits.
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+
+        xor_shifted.rotate_right(rotation)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    // A number in [0, 1), with all 24 bits of an f32 mantissa random.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+    }
+
+    // A number in [0, 1), with all 53 bits of an f64 mantissa random.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    pub fn next_bool(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    // A number in [min, max), or min when min == max.
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        let value = min + (max - min) * self.next_f32();
+
+        // When the range is small compared to min, the sum can round up to exactly max, so it is moved to the number just below.
+        if value < max || min >= max { value } else { previous_f32(max) }
+    }
+
+    // A number in [min, max). Every number is equally likely, which "next_u32() % n" would not give.
+    // Panics if the range is empty.
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        assert!(min < max, "range_i32 needs min < max, but got {} and {}", min, max);
+
+        let span = (max as i64 - min as i64) as u32;
+        (min as i64 + self.below(span) as i64) as i32
+    }
+
+    // A number in [0, bound), rejecting the lowest values that would make some results more likely than others.
+    fn below(&mut self, bound: u32) -> u32 {
+        let threshold = bound.wrapping_neg() % bound;
+
+        loop {
+            let value = self.next_u32();
+
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn in_unit_disc(&mut self) -> Vector2 {
+        loop {
+            let point = Vector2::new(self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0));
+
+            if point.length_squared() <= 1.0 {
+                return point;
+            }
+        }
+    }
+
+    pub fn on_unit_circle(&mut self) -> Vector2 {
+        loop {
+            let point = self.in_unit_disc();
+            let length_squared = point.length_squared();
+
+            if length_squared > 1e-6 {
+                return point / length_squared.sqrt();
+            }
+        }
+    }
+
+    pub fn in_unit_sphere(&mut self) -> Vector3 {
+        loop {
+            let point = Vector3::new(self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0));
+
+            if point.length_squared() <= 1.0 {
+                return point;
+            }
+        }
+    }
+
+    // A random direction, with every direction equally likely.
+    pub fn on_unit_sphere(&mut self) -> Vector3 {
+        loop {
+            let point = self.in_unit_sphere();
+            let length_squared = point.length_squared();
+
+            if length_squared > 1e-6 {
+                return point / length_squared.sqrt();
+            }
+        }
+    }
+
+    // A random rotation, with every orientation equally likely.
+    // Points picked evenly inside the 4D unit ball and moved out to its surface give evenly distributed unit quaternions.
+    pub fn quaternion(&mut self) -> Quaternion {
+        loop {
+            let q = Quaternion::new(self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0), self.range_f32(-1.0, 1.0));
+            let length_squared = q.dot(&q);
+
+            if length_squared > 1e-6 && length_squared <= 1.0 {
+                return q.normalize();
+            }
+        }
+    }
+
+    // Picks an index with a probability proportional to its weight. Negative weights count as zero.
+    // Returns None if there are no weights, they are all zero, or they sum to infinity.
+    pub fn weighted_choice(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().map(|weight| weight.max(0.0)).sum();
+
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+
+        let mut remaining = self.next_f32() * total;
+        let mut last_positive = None;
+
+        for (index, weight) in weights.iter().enumerate() {
+            let weight = weight.max(0.0);
+
+            if weight == 0.0 {
+                continue;
+            }
+
+            if remaining < weight {
+                return Some(index);
+            }
+
+            remaining -= weight;
+            last_positive = Some(index);
+        }
+
+        // Rounding in the sum can leave a tiny remainder after the last weight.
+        last_positive
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        Some(&items[self.below(items.len() as u32) as usize])
+    }
+
+    // Shuffles the items in place with the Fisher-Yates algorithm, where every order is equally likely.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+// The largest f32 below "value".
+fn previous_f32(value: f32) -> f32 {
+    if value == 0.0 {
+        -f32::from_bits(1)
+    } else if value > 0.0 {
+        f32::from_bits(value.to_bits() - 1)
+    } else {
+        f32::from_bits(value.to_bits() + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    #[test]
+    fn should_match_reference_implementation_when_seeded() {
+        // Arrange
+        // The first numbers of the reference C implementation of PCG32, seeded with pcg32_srandom_r(&rng, 42, 54).
+        let expected: [u32; 6] = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+        let mut random = Random::with_stream(42, 54);
+
+        // Act
+        let numbers: Vec<u32> = (0..6).map(|_| random.next_u32()).collect();
+
+        // Assert
+        assert_eq!(numbers, expected);
+    }
+
+    #[test]
+    fn should_give_identical_sequences_when_using_same_seed() {
+        // Arrange
+        let mut first = Random::new(1969);
+        let mut second = Random::new(1969);
+        let mut other_stream = Random::with_stream(1969, 7);
+
+        // Act
+        let first_points: Vec<Vector3> = (0..100).map(|_| first.on_unit_sphere()).collect();
+        let second_points: Vec<Vector3> = (0..100).map(|_| second.on_unit_sphere()).collect();
+        let other_points: Vec<Vector3> = (0..100).map(|_| other_stream.on_unit_sphere()).collect();
+
+        // Assert
+        assert_eq!(first_points, second_points);
+        assert_ne!(first_points, other_points);
+    }
+
+    #[test]
+    fn should_stay_within_bounds_when_sampling() {
+        // Arrange
+        let mut random = Random::new(7);
+
+        for _ in 0..1000 {
+            // Act
+            let value = random.range_f32(-3.0, 5.0);
+            let integer = random.range_i32(-2, 3);
+            let disc = random.in_unit_disc();
+            let direction = random.on_unit_sphere();
+            let rotation = random.quaternion();
+
+            // Assert
+            assert!((-3.0..5.0).contains(&value));
+            assert!((-2..3).contains(&integer));
+            assert!(disc.length() <= 1.0);
+            assert!(direction.length().approx_eq(&1.0));
+            assert!(rotation.length().approx_eq(&1.0));
+        }
+    }
+
+    #[test]
+    fn should_stay_below_max_when_range_is_small_compared_to_min() {
+        // Arrange
+        // f32 numbers around 1e8 are 8 apart, so without care about half of the samples would round up to max.
+        let mut random = Random::new(3);
+        let (min, max) = (1.0e8, 1.0e8 + 8.0);
+
+        for _ in 0..100 {
+            // Act
+            let value = random.range_f32(min, max);
+
+            // Assert
+            assert!((min..max).contains(&value));
+        }
+
+        assert_eq!(random.range_f32(2.0, 2.0), 2.0);
+    }
+
+    #[test]
+    fn should_pick_in_proportion_to_weights_when_making_weighted_choice() {
+        // Arrange
+        let mut random = Random::new(3);
+        let weights = [1.0, 0.0, 3.0, -2.0];
+        let mut counts = [0; 4];
+
+        // Act
+        for _ in 0..10000 {
+            counts[random.weighted_choice(&weights).unwrap()] += 1;
+        }
+
+        // Assert
+        assert_eq!(counts[1] + counts[3], 0);
+        assert!((counts[2] as f32 / counts[0] as f32 - 3.0).abs() < 0.3);
+        assert_eq!(random.weighted_choice(&[0.0, -1.0]), None);
+        assert_eq!(random.weighted_choice(&[]), None);
+    }
+
+    #[test]
+    fn should_keep_every_item_when_shuffling() {
+        // Arrange
+        let mut random = Random::new(11);
+        let mut items: Vec<i32> = (0..50).collect();
+
+        // Act
+        random.shuffle(&mut items);
+
+        // Assert
+        assert_ne!(items, (0..50).collect::<Vec<i32>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<i32>>());
+    }
+}
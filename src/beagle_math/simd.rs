@@ -0,0 +1,370 @@
+use crate::beagle_math::{Matrix4, Vec3};
+
+// The Mat4 operations that are run every frame (multiplication, transpose, inverse and transforming batches of points)
+// are implemented through this trait, so f32 can use SSE2 instructions while f64 uses the plain implementations in Matrix4.
+// The trait is only visible inside beagle_math, and is implemented for exactly the scalar types implementing Real.
+pub trait MatrixKernels: Sized {
+    fn mat4_mul(a: &Matrix4<Self>, b: &Matrix4<Self>) -> Matrix4<Self>;
+
+    fn mat4_transpose(mat: &Matrix4<Self>) -> Matrix4<Self>;
+
+    fn mat4_inverse(mat: &Matrix4<Self>) -> Option<Matrix4<Self>>;
+
+    fn mat4_transform_points(mat: &Matrix4<Self>, points: &[Vec3<Self>]) -> Vec<Vec3<Self>>;
+}
+
+impl MatrixKernels for f64 {
+    fn mat4_mul(a: &Matrix4<f64>, b: &Matrix4<f64>) -> Matrix4<f64> {
+        a.mul_scalar(b)
+    }
+
+    fn mat4_transpose(mat: &Matrix4<f64>) -> Matrix4<f64> {
+        mat.transpose_scalar()
+    }
+
+    fn mat4_inverse(mat: &Matrix4<f64>) -> Option<Matrix4<f64>> {
+        mat.inverse_scalar()
+    }
+
+    fn mat4_transform_points(mat: &Matrix4<f64>, points: &[Vec3<f64>]) -> Vec<Vec3<f64>> {
+        mat.transform_points_scalar(points)
+    }
+}
+
+// SSE2 is part of the x86_64 baseline, so it can be used without checking the CPU at runtime.
+// The SSE2 inverse rounds differently from the scalar one, so the deterministic feature uses the scalar code on every CPU.
+#[cfg(all(target_arch = "x86_64", not(feature = "deterministic")))]
+impl MatrixKernels for f32 {
+    fn mat4_mul(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        sse2::mul(a, b)
+    }
+
+    fn mat4_transpose(mat: &Matrix4<f32>) -> Matrix4<f32> {
+        sse2::transpose(mat)
+    }
+
+    fn mat4_inverse(mat: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+        sse2::inverse(mat)
+    }
+
+    fn mat4_transform_points(mat: &Matrix4<f32>, points: &[Vec3<f32>]) -> Vec<Vec3<f32>> {
+        sse2::transform_points(mat, points)
+    }
+}
+
+#[cfg(any(not(target_arch = "x86_64"), feature = "deterministic"))]
+impl MatrixKernels for f32 {
+    fn mat4_mul(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        a.mul_scalar(b)
+    }
+
+    fn mat4_transpose(mat: &Matrix4<f32>) -> Matrix4<f32> {
+        mat.transpose_scalar()
+    }
+
+    fn mat4_inverse(mat: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+        mat.inverse_scalar()
+    }
+
+    fn mat4_transform_points(mat: &Matrix4<f32>, points: &[Vec3<f32>]) -> Vec<Vec3<f32>> {
+        mat.transform_points_scalar(points)
+    }
+}
+
+// Each row of a Mat4 fits exactly in one 128 bit SSE register.
+// Since the rows are stored consecutively, they can be loaded straight from the matrix array.
+#[cfg(target_arch = "x86_64")]
+#[cfg_attr(feature = "deterministic", allow(dead_code))]
+mod sse2 {
+    use std::arch::x86_64::*;
+
+    use crate::beagle_math::{Matrix4, Vec3};
+
+    // Selects elements for _mm_shuffle_ps. The first two elements of the result come from "a", the last two from "b".
+    const fn shuffle_mask(x: i32, y: i32, z: i32, w: i32) -> i32 {
+        x | (y << 2) | (z << 4) | (w << 6)
+    }
+
+    unsafe fn load_rows(mat: &Matrix4<f32>) -> [__m128; 4] {
+        let m = mat.matrix.as_ptr();
+
+        [_mm_loadu_ps(m), _mm_loadu_ps(m.add(4)), _mm_loadu_ps(m.add(8)), _mm_loadu_ps(m.add(12))]
+    }
+
+    unsafe fn store_rows(rows: [__m128; 4]) -> Matrix4<f32> {
+        let mut matrix = [0.0; 16];
+
+        for (index, row) in rows.iter().enumerate() {
+            _mm_storeu_ps(matrix.as_mut_ptr().add(index * 4), *row);
+        }
+
+        Matrix4::new(matrix)
+    }
+
+    // Row i of the result is the i'th row of "a" multiplied with "b", which is the rows of "b" weighted by the elements of that row.
+    // The additions happen in the same order as the dot products of the scalar version, so both give exactly the same result.
+    pub fn mul(a: &Matrix4<f32>, b: &Matrix4<f32>) -> Matrix4<f32> {
+        unsafe {
+            let b_rows = load_rows(b);
+            let mut rows = [_mm_setzero_ps(); 4];
+
+            for (index, row) in rows.iter_mut().enumerate() {
+                let a_row = &a.matrix[index * 4..index * 4 + 4];
+
+                let mut result = _mm_mul_ps(_mm_set1_ps(a_row[0]), b_rows[0]);
+                result = _mm_add_ps(result, _mm_mul_ps(_mm_set1_ps(a_row[1]), b_rows[1]));
+                result = _mm_add_ps(result, _mm_mul_ps(_mm_set1_ps(a_row[2]), b_rows[2]));
+                result = _mm_add_ps(result, _mm_mul_ps(_mm_set1_ps(a_row[3]), b_rows[3]));
+
+                *row = result;
+            }
+
+            store_rows(rows)
+        }
+    }
+
+    pub fn transpose(mat: &Matrix4<f32>) -> Matrix4<f32> {
+        unsafe {
+            let [row0, row1, row2, row3] = load_rows(mat);
+
+            // (m00, m10, m01, m11), (m02, m12, m03, m13), and the same for the lower two rows.
+            let upper_low = _mm_unpacklo_ps(row0, row1);
+            let upper_high = _mm_unpackhi_ps(row0, row1);
+            let lower_low = _mm_unpacklo_ps(row2, row3);
+            let lower_high = _mm_unpackhi_ps(row2, row3);
+
+            store_rows([
+                _mm_movelh_ps(upper_low, lower_low),
+                _mm_movehl_ps(lower_low, upper_low),
+                _mm_movelh_ps(upper_high, lower_high),
+                _mm_movehl_ps(lower_high, upper_high)
+            ])
+        }
+    }
+
+    // The helpers below work on 2x2 matrices stored in one register as (m00, m01, m10, m11).
+
+    // A * B
+    unsafe fn mat2_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_add_ps(
+            _mm_mul_ps(a, _mm_shuffle_ps::<{ shuffle_mask(0, 3, 0, 3) }>(b, b)),
+            _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(1, 0, 3, 2) }>(a, a), _mm_shuffle_ps::<{ shuffle_mask(2, 1, 2, 1) }>(b, b)))
+    }
+
+    // adjugate(A) * B
+    unsafe fn mat2_adjugate_mul(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(3, 3, 0, 0) }>(a, a), b),
+            _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(1, 1, 2, 2) }>(a, a), _mm_shuffle_ps::<{ shuffle_mask(2, 3, 0, 1) }>(b, b)))
+    }
+
+    // A * adjugate(B)
+    unsafe fn mat2_mul_adjugate(a: __m128, b: __m128) -> __m128 {
+        _mm_sub_ps(
+            _mm_mul_ps(a, _mm_shuffle_ps::<{ shuffle_mask(3, 0, 3, 0) }>(b, b)),
+            _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(1, 0, 3, 2) }>(a, a), _mm_shuffle_ps::<{ shuffle_mask(2, 1, 2, 1) }>(b, b)))
+    }
+
+    unsafe fn splat(value: __m128, index: i32) -> __m128 {
+        match index {
+            0 => _mm_shuffle_ps::<{ shuffle_mask(0, 0, 0, 0) }>(value, value),
+            1 => _mm_shuffle_ps::<{ shuffle_mask(1, 1, 1, 1) }>(value, value),
+            2 => _mm_shuffle_ps::<{ shuffle_mask(2, 2, 2, 2) }>(value, value),
+            _ => _mm_shuffle_ps::<{ shuffle_mask(3, 3, 3, 3) }>(value, value)
+        }
+    }
+
+    // Inverts the matrix by splitting it into the 2x2 blocks [A B; C D], and building the blocks of the inverse from the
+    // adjugates and determinants of A, B, C and D. This needs far fewer shuffles than working with the full 4x4 cofactors.
+    // Since the operations happen in a different order than the scalar version, the results can differ in the last bits.
+    pub fn inverse(mat: &Matrix4<f32>) -> Option<Matrix4<f32>> {
+        unsafe {
+            let [row0, row1, row2, row3] = load_rows(mat);
+
+            let a = _mm_movelh_ps(row0, row1);
+            let b = _mm_movehl_ps(row1, row0);
+            let c = _mm_movelh_ps(row2, row3);
+            let d = _mm_movehl_ps(row3, row2);
+
+            // The determinants of the blocks as (|A|, |B|, |C|, |D|).
+            let block_determinants = _mm_sub_ps(
+                _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(0, 2, 0, 2) }>(row0, row2), _mm_shuffle_ps::<{ shuffle_mask(1, 3, 1, 3) }>(row1, row3)),
+                _mm_mul_ps(_mm_shuffle_ps::<{ shuffle_mask(1, 3, 1, 3) }>(row0, row2), _mm_shuffle_ps::<{ shuffle_mask(0, 2, 0, 2) }>(row1, row3)));
+
+            let det_a = splat(block_determinants, 0);
+            let det_b = splat(block_determinants, 1);
+            let det_c = splat(block_determinants, 2);
+            let det_d = splat(block_determinants, 3);
+
+            let d_c = mat2_adjugate_mul(d, c);
+            let a_b = mat2_adjugate_mul(a, b);
+
+            // The adjugates of the blocks of the inverse, which is 1/|M| * [X Y; Z W].
+            let x = _mm_sub_ps(_mm_mul_ps(det_d, a), mat2_mul(b, d_c));
+            let w = _mm_sub_ps(_mm_mul_ps(det_a, d), mat2_mul(c, a_b));
+            let y = _mm_sub_ps(_mm_mul_ps(det_b, c), mat2_mul_adjugate(d, a_b));
+            let z = _mm_sub_ps(_mm_mul_ps(det_c, b), mat2_mul_adjugate(a, d_c));
+
+            // |M| = |A||D| + |B||C| - trace(adjugate(A)B * adjugate(D)C)
+            let trace = _mm_mul_ps(a_b, _mm_shuffle_ps::<{ shuffle_mask(0, 2, 1, 3) }>(d_c, d_c));
+            let trace = _mm_add_ps(trace, _mm_shuffle_ps::<{ shuffle_mask(2, 3, 0, 1) }>(trace, trace));
+            let trace = _mm_add_ps(trace, _mm_shuffle_ps::<{ shuffle_mask(1, 0, 3, 2) }>(trace, trace));
+
+            let det = _mm_sub_ps(_mm_add_ps(_mm_mul_ps(det_a, det_d), _mm_mul_ps(det_b, det_c)), trace);
+            let det_value = _mm_cvtss_f32(det);
+
+            if det_value == 0.0 || !det_value.is_finite() {
+                return None;
+            }
+
+            // Dividing the signs of the adjugate by the determinant at the same time.
+            let inverse_det = _mm_div_ps(_mm_setr_ps(1.0, -1.0, -1.0, 1.0), det);
+
+            let x = _mm_mul_ps(x, inverse_det);
+            let y = _mm_mul_ps(y, inverse_det);
+            let z = _mm_mul_ps(z, inverse_det);
+            let w = _mm_mul_ps(w, inverse_det);
+
+            // Taking the adjugate of each block and placing the blocks back into rows.
+            Some(store_rows([
+                _mm_shuffle_ps::<{ shuffle_mask(3, 1, 3, 1) }>(x, y),
+                _mm_shuffle_ps::<{ shuffle_mask(2, 0, 2, 0) }>(x, y),
+                _mm_shuffle_ps::<{ shuffle_mask(3, 1, 3, 1) }>(z, w),
+                _mm_shuffle_ps::<{ shuffle_mask(2, 0, 2, 0) }>(z, w)
+            ]))
+        }
+    }
+
+    // Transforms every point as the row vector (x, y, z, 1), followed by the perspective divide when w is not 1.
+    // Like "mul", this gives exactly the same result as the scalar version.
+    pub fn transform_points(mat: &Matrix4<f32>, points: &[Vec3<f32>]) -> Vec<Vec3<f32>> {
+        unsafe {
+            let [row0, row1, row2, row3] = load_rows(mat);
+            let mut result = [0.0f32; 4];
+
+            points.iter().map(|point| {
+                let mut transformed = _mm_mul_ps(_mm_set1_ps(point.x), row0);
+                transformed = _mm_add_ps(transformed, _mm_mul_ps(_mm_set1_ps(point.y), row1));
+                transformed = _mm_add_ps(transformed, _mm_mul_ps(_mm_set1_ps(point.z), row2));
+                transformed = _mm_add_ps(transformed, row3);
+
+                _mm_storeu_ps(result.as_mut_ptr(), transformed);
+
+                if result[3] == 1.0 {
+                    Vec3::new(result[0], result[1], result[2])
+                } else {
+                    Vec3::new(result[0] / result[3], result[1] / result[3], result[2] / result[3])
+                }
+            }).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::beagle_math::*;
+
+    fn test_matrices() -> Vec<Mat4> {
+        vec![
+            Mat4::identity(),
+            Mat4::rotate_x(0.3).mul_scalar(&Mat4::rotate_y(-1.2)).mul_scalar(&Mat4::translate(&Vector3::new(4.0, -2.0, 9.0))),
+            Mat4::projection(1.2, 800.0, 600.0, 0.1, 100.0),
+            Mat4::new([
+                1.0, 8.0, 2.0, 3.0,
+                2.0, 1.0, 1.0, 1.0,
+                3.0, 2.0, 4.0, 3.0,
+                1.0, 9.0, 4.0, 3.0
+            ]),
+            Mat4::new([
+                0.5, -3.0, 7.0, 0.25,
+                -1.5, 2.0, 0.0, 4.0,
+                6.0, 1.0, -2.0, 0.5,
+                3.0, -0.75, 1.0, 8.0
+            ])
+        ]
+    }
+
+    #[test]
+    fn should_match_scalar_path_when_multiplying_and_transposing() {
+        for a in test_matrices() {
+            for b in test_matrices() {
+                // Act
+                let fast = a.mul(&b);
+                let scalar = a.mul_scalar(&b);
+
+                // Assert
+                assert_eq!(fast.get_value(), scalar.get_value());
+            }
+
+            assert_eq!(a.get_column_major_value(), a.transpose_scalar().get_value());
+        }
+    }
+
+    #[test]
+    fn should_match_scalar_path_when_inverting() {
+        for matrix in test_matrices() {
+            // Act
+            let fast = matrix.inverse().expect("Matrix should be invertible.");
+            let scalar = matrix.inverse_scalar().expect("Matrix should be invertible.");
+
+            // Assert
+            for (f, s) in fast.get_value().iter().zip(scalar.get_value().iter()) {
+                assert!((f - s).abs() < 1e-4 * s.abs().max(1.0), "Expected {:?} but got {:?}", scalar, fast);
+            }
+        }
+
+        assert!(Mat4::scale(&Vector3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn should_match_scalar_path_when_transforming_batch_of_points() {
+        // Arrange
+        let points: Vec<Vector3> = (0..37).map(|i| Vector3::new(i as f32 * 0.5, -(i as f32), 3.0 - i as f32 * 0.25)).collect();
+
+        for matrix in test_matrices() {
+            // Act
+            let fast = matrix.transform_points(&points);
+            let scalar = matrix.transform_points_scalar(&points);
+
+            // Assert
+            for (f, s) in fast.iter().zip(scalar.iter()) {
+                assert_eq!((f.x, f.y, f.z), (s.x, s.y, s.z));
+            }
+        }
+    }
+
+    // Compares the SIMD and scalar paths. It is ignored by default, since timings are only meaningful in release builds:
+    // cargo test --release -- --ignored --nocapture benchmark
+    #[test]
+    #[ignore]
+    fn benchmark_simd_against_scalar() {
+        const ITERATIONS: usize = 1_000_000;
+
+        let matrices = test_matrices();
+        let points: Vec<Vector3> = (0..1000).map(|i| Vector3::new(i as f32, 1.0, -(i as f32))).collect();
+
+        let time = |name: &str, operation: &dyn Fn(usize) -> f32| {
+            let start = Instant::now();
+            let mut checksum = 0.0;
+
+            for i in 0..ITERATIONS {
+                checksum += operation(i);
+            }
+
+            println!("{:<32} {:>8.2} ns/iteration (checksum {})", name, start.elapsed().as_nanos() as f64 / ITERATIONS as f64, checksum);
+        };
+
+        let pick = |i: usize| (&matrices[i % matrices.len()], &matrices[(i + 1) % matrices.len()]);
+
+        time("mul (simd)", &|i| { let (a, b) = pick(i); a.mul(b).get(0, 0) });
+        time("mul (scalar)", &|i| { let (a, b) = pick(i); a.mul_scalar(b).get(0, 0) });
+        time("transpose (simd)", &|i| pick(i).0.get_column_major_value()[1]);
+        time("transpose (scalar)", &|i| pick(i).0.transpose_scalar().get(1, 0));
+        time("inverse (simd)", &|i| pick(i).1.inverse().map_or(0.0, |m| m.get(0, 0)));
+        time("inverse (scalar)", &|i| pick(i).1.inverse_scalar().map_or(0.0, |m| m.get(0, 0)));
+        time("1000 points (simd)", &|i| if i % 1000 == 0 { pick(i).0.transform_points(&points)[999].x } else { 0.0 });
+        time("1000 points (scalar)", &|i| if i % 1000 == 0 { pick(i).0.transform_points_scalar(&points)[999].x } else { 0.0 });
+    }
+}
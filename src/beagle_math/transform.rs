@@ -0,0 +1,211 @@
+use crate::beagle_math::{Mat4, Quaternion, Vector3};
+
+// A transform made of a translation, a rotation and a scale (TRS).
+// Following the row vector convention of the library, a point is first scaled, then rotated and lastly translated,
+// so the matrix of a transform is S * R * T.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    pub fn new(translation: Vector3, rotation: Quaternion, scale: Vector3) -> Transform {
+        Transform {
+            translation,
+            rotation,
+            scale
+        }
+    }
+
+    pub fn identity() -> Transform {
+        Transform::new(Vector3::default(), Quaternion::identity(), Vector3::new(1.0, 1.0, 1.0))
+    }
+
+    // Decomposes an affine matrix back into translation, rotation and scale.
+    // The matrix must not contain shear, as that cannot be represented by a Transform.
+    // A mirroring matrix (negative determinant) is represented by a negative X scale.
+    // Returns None if the matrix has a zero scale on any axis, as the rotation is then undefined.
+    pub fn from_mat4(mat: &Mat4) -> Option<Transform> {
+        let m = &mat.matrix;
+
+        let row0 = Vector3::new(m[0], m[1], m[2]);
+        let row1 = Vector3::new(m[4], m[5], m[6]);
+        let row2 = Vector3::new(m[8], m[9], m[10]);
+
+        let mut scale = Vector3::new(row0.length(), row1.length(), row2.length());
+
+        if scale.x == 0.0 || scale.y == 0.0 || scale.z == 0.0 {
+            return None;
+        }
+
+        if row0.dot(&row1.cross(&row2)) < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        let row0 = row0 / scale.x;
+        let row1 = row1 / scale.y;
+        let row2 = row2 / scale.z;
+
+        let rotation = Quaternion::from_mat4(&Mat4::new([
+            row0.x, row0.y, row0.z, 0.0,
+            row1.x, row1.y, row1.z, 0.0,
+            row2.x, row2.y, row2.z, 0.0,
+            0.0   , 0.0   , 0.0   , 1.0
+        ]));
+
+        Some(Transform::new(Vector3::new(m[12], m[13], m[14]), rotation, scale))
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        Mat4::scale(&self.scale)
+            .mul(&self.rotation.to_mat4())
+            .mul(&Mat4::translate(&self.translation))
+    }
+
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        self.transform_vector(point) + self.translation
+    }
+
+    // Transforms a direction, which is affected by scale and rotation, but not by translation.
+    pub fn transform_vector(&self, vector: &Vector3) -> Vector3 {
+        self.rotation.rotate_vector(&scale_component_wise(vector, &self.scale))
+    }
+
+    // Combines this transform as the parent with a child transform given relative to it.
+    // The result places the child directly in the space this transform is relative to, so that
+    // parent.compose(&child).to_mat4() equals child.to_mat4().mul(&parent.to_mat4()).
+    // A TRS transform cannot represent the shear created when a non-uniformly scaled parent has a rotated child,
+    // so in that case the result is only an approximation.
+    pub fn compose(&self, child: &Transform) -> Transform {
+        Transform::new(
+            self.transform_point(&child.translation),
+            child.rotation.mul(&self.rotation),
+            scale_component_wise(&self.scale, &child.scale)
+        )
+    }
+
+    // The inverse transform, undoing the translation, then the rotation and lastly the scale.
+    // Like "compose", this is exact for uniform scale, but only an approximation for non-uniform scale combined with rotation.
+    pub fn inverse(&self) -> Transform {
+        let inverse_scale = Vector3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        let inverse_rotation = self.rotation.conjugate();
+        let inverse_translation = scale_component_wise(&inverse_rotation.rotate_vector(&-self.translation), &inverse_scale);
+
+        Transform::new(inverse_translation, inverse_rotation, inverse_scale)
+    }
+
+    // Interpolates between two transforms, with linear interpolation of translation and scale, and slerp of the rotation.
+    pub fn lerp(&self, transform: &Transform, t: f32) -> Transform {
+        Transform::new(
+            self.translation.lerp(&transform.translation, t),
+            self.rotation.slerp(&transform.rotation, t),
+            self.scale.lerp(&transform.scale, t)
+        )
+    }
+}
+
+fn scale_component_wise(vector: &Vector3, scale: &Vector3) -> Vector3 {
+    Vector3::new(vector.x * scale.x, vector.y * scale.y, vector.z * scale.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+
+    fn lander_transform() -> Transform {
+        Transform::new(
+            Vector3::new(10.0, 250.0, -3.0),
+            Quaternion::from_axis_angle(&Vector3::new(0.3, 1.0, 0.2), 0.7),
+            Vector3::new(2.0, 2.0, 2.0))
+    }
+
+    #[test]
+    fn should_scale_then_rotate_then_translate_when_transforming_point() {
+        // Arrange
+        let transform = Transform::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 90.0f32.to_radians()),
+            Vector3::new(2.0, 1.0, 1.0));
+
+        // Act
+        let point = transform.transform_point(&Vector3::new(1.0, 0.0, 0.0));
+        let vector = transform.transform_vector(&Vector3::new(1.0, 0.0, 0.0));
+
+        // Assert
+        assert!(point.approx_eq(&Vector3::new(1.0, 2.0, 1.0)));
+        assert!(vector.approx_eq(&Vector3::new(0.0, 0.0, -2.0)));
+    }
+
+    #[test]
+    fn should_match_matrix_multiplication_when_composing_transforms() {
+        // Arrange
+        let parent = lander_transform();
+        let child = Transform::new(
+            Vector3::new(1.5, -1.0, 0.0),
+            Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), -0.4),
+            Vector3::new(1.0, 0.5, 1.0));
+
+        // Act
+        let composed = parent.compose(&child);
+
+        // Assert
+        assert!(composed.to_mat4().approx_eq(&child.to_mat4().mul(&parent.to_mat4())));
+    }
+
+    #[test]
+    fn should_return_identity_when_composing_transform_with_its_inverse() {
+        // Arrange
+        let transform = lander_transform();
+
+        // Act
+        let identity = transform.compose(&transform.inverse());
+
+        // Assert
+        assert!(identity.to_mat4().approx_eq_eps(&Mat4::identity(), 1e-4));
+        assert!(transform.inverse().to_mat4().approx_eq(&transform.to_mat4().inverse().unwrap()));
+    }
+
+    #[test]
+    fn should_return_original_transform_when_decomposing_matrix() {
+        // Arrange
+        let transform = Transform::new(
+            Vector3::new(-4.0, 1.0, 8.0),
+            Quaternion::from_axis_angle(&Vector3::new(1.0, -1.0, 0.5), 2.1),
+            Vector3::new(3.0, 0.5, 1.5));
+
+        // Act
+        let decomposed = Transform::from_mat4(&transform.to_mat4()).expect("Matrix should be decomposable.");
+
+        // Assert
+        assert!(decomposed.translation.approx_eq(&transform.translation));
+        assert!(decomposed.scale.approx_eq(&transform.scale));
+        assert!(decomposed.rotation.dot(&transform.rotation).abs() > 0.9999);
+        assert!(Transform::from_mat4(&Mat4::scale(&Vector3::new(1.0, 0.0, 1.0))).is_none());
+    }
+
+    #[test]
+    fn should_return_endpoints_when_interpolating_transforms() {
+        // Arrange
+        let start = Transform::identity();
+        let end = lander_transform();
+
+        // Act
+        let at_start = start.lerp(&end, 0.0);
+        let at_end = start.lerp(&end, 1.0);
+        let halfway = start.lerp(&end, 0.5);
+
+        // Assert
+        assert!(at_start.to_mat4().approx_eq(&start.to_mat4()));
+        assert!(at_end.to_mat4().approx_eq(&end.to_mat4()));
+        assert!(halfway.translation.approx_eq(&Vector3::new(5.0, 125.0, -1.5)));
+        assert!(halfway.scale.approx_eq(&Vector3::new(1.5, 1.5, 1.5)));
+    }
+}
@@ -0,0 +1,289 @@
+use std::f32::consts::PI;
+
+use crate::beagle_math::{math, Quat, Real, Transform, Vec2, Vec3, Vec4};
+
+// The shape of an easing function, as the "in" version that starts slowly and speeds up towards the end.
+// See https://easings.net for what each of them looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EasingCurve {
+    Quad,
+    Cubic,
+    Expo,
+    Elastic,
+    Back,
+    Bounce
+}
+
+// Maps the linear progress of an animation in [0, 1] to an eased progress.
+// Every easing gives 0.0 at the start and 1.0 at the end, but Elastic and Back overshoot outside [0, 1] on the way.
+// Out is the "in" curve played backwards (starting fast and slowing down), and InOut is the "in" curve for the first half
+// followed by the "out" curve for the second half.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    In(EasingCurve),
+    Out(EasingCurve),
+    InOut(EasingCurve)
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::In(curve) => ease_in(*curve, t),
+            Easing::Out(curve) => 1.0 - ease_in(*curve, 1.0 - t),
+            Easing::InOut(curve) => {
+                if t < 0.5 {
+                    ease_in(*curve, 2.0 * t) / 2.0
+                } else {
+                    1.0 - ease_in(*curve, 2.0 - 2.0 * t) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn ease_in(curve: EasingCurve, t: f32) -> f32 {
+    match curve {
+        EasingCurve::Quad => t * t,
+        EasingCurve::Cubic => t * t * t,
+        EasingCurve::Expo => {
+            // 2^(10t - 10) does not quite reach zero at the start, so the end points are given exactly.
+            if t <= 0.0 { 0.0 } else if t >= 1.0 { 1.0 } else { math::powf(2.0, 10.0 * t - 10.0) }
+        },
+        EasingCurve::Elastic => {
+            if t <= 0.0 {
+                0.0
+            } else if t >= 1.0 {
+                1.0
+            } else {
+                -math::powf(2.0, 10.0 * t - 10.0) * math::sin((10.0 * t - 10.75) * (2.0 * PI / 3.0))
+            }
+        },
+        EasingCurve::Back => {
+            // The amount of overshoot, which gives a dip of about 10% below zero.
+            const OVERSHOOT: f32 = 1.70158;
+            (OVERSHOOT + 1.0) * t * t * t - OVERSHOOT * t * t
+        },
+        EasingCurve::Bounce => 1.0 - bounce_out(1.0 - t)
+    }
+}
+
+// Four parabolas of decreasing height, like a ball bouncing to rest.
+fn bounce_out(t: f32) -> f32 {
+    const STRENGTH: f32 = 7.5625;
+    const WIDTH: f32 = 2.75;
+
+    if t < 1.0 / WIDTH {
+        STRENGTH * t * t
+    } else if t < 2.0 / WIDTH {
+        let t = t - 1.5 / WIDTH;
+        STRENGTH * t * t + 0.75
+    } else if t < 2.5 / WIDTH {
+        let t = t - 2.25 / WIDTH;
+        STRENGTH * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / WIDTH;
+        STRENGTH * t * t + 0.984375
+    }
+}
+
+// Types that can be interpolated between two values, giving "self" at t = 0.0 and "other" at t = 1.0.
+// The easings can move t outside [0, 1], so implementations should extrapolate instead of clamping.
+pub trait Lerp {
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &f32, t: f32) -> f32 {
+        self + (other - self) * t
+    }
+}
+
+macro_rules! impl_lerp_for_vector {
+    ($vector:ident) => {
+        impl<T: Real> Lerp for $vector<T> {
+            fn lerp(&self, other: &$vector<T>, t: f32) -> $vector<T> {
+                $vector::lerp(self, other, T::from_f64(t as f64))
+            }
+        }
+    };
+}
+
+impl_lerp_for_vector!(Vec2);
+impl_lerp_for_vector!(Vec3);
+impl_lerp_for_vector!(Vec4);
+
+impl<T: Real> Lerp for Quat<T> {
+    fn lerp(&self, other: &Quat<T>, t: f32) -> Quat<T> {
+        self.slerp(other, T::from_f64(t as f64))
+    }
+}
+
+impl Lerp for Transform {
+    fn lerp(&self, other: &Transform, t: f32) -> Transform {
+        Transform::lerp(self, other, t)
+    }
+}
+
+// Animates a value from "start" to "end" over "duration" seconds, following an easing.
+// The tween is driven by calling "update" with the frame time every frame, which also returns the current value.
+// The completion callback is called once, in the update where the tween reaches its end.
+pub struct Tween<T: Lerp> {
+    start: T,
+    end: T,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+    on_complete: Option<Box<dyn FnMut()>>,
+    completed: bool
+}
+
+impl<T: Lerp> Tween<T> {
+    // A duration of zero or less makes the tween finish in its first update.
+    pub fn new(start: T, end: T, duration: f32, easing: Easing) -> Tween<T> {
+        Tween {
+            start,
+            end,
+            duration,
+            elapsed: 0.0,
+            easing,
+            on_complete: None,
+            completed: false
+        }
+    }
+
+    pub fn on_complete<F: FnMut() + 'static>(mut self, callback: F) -> Tween<T> {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    pub fn update(&mut self, delta_time: f32) -> T {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration.max(0.0));
+
+        if self.is_finished() && !self.completed {
+            self.completed = true;
+
+            if let Some(callback) = self.on_complete.as_mut() {
+                callback();
+            }
+        }
+
+        self.value()
+    }
+
+    pub fn value(&self) -> T {
+        self.start.lerp(&self.end, self.easing.apply(self.progress()))
+    }
+
+    // The linear progress in [0, 1], before the easing is applied.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return if self.completed { 1.0 } else { 0.0 };
+        }
+
+        self.elapsed / self.duration
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    // Starts the tween over, so the completion callback can be called again.
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.completed = false;
+    }
+
+    // Replaces the target while keeping the current value as the new start, such as when the camera should zoom further
+    // before the previous zoom has finished.
+    pub fn retarget(&mut self, end: T) {
+        self.start = self.value();
+        self.end = end;
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::beagle_math::*;
+
+    const CURVES: [EasingCurve; 6] = [EasingCurve::Quad, EasingCurve::Cubic, EasingCurve::Expo, EasingCurve::Elastic, EasingCurve::Back, EasingCurve::Bounce];
+
+    #[test]
+    fn should_start_at_zero_and_end_at_one_for_every_easing() {
+        for curve in CURVES.iter() {
+            for easing in [Easing::In(*curve), Easing::Out(*curve), Easing::InOut(*curve)].iter() {
+                // Assert
+                assert!(easing.apply(0.0).abs() < 1e-6, "{:?}", easing);
+                assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+            }
+
+            assert!((Easing::InOut(*curve).apply(0.5) - 0.5).abs() < 1e-6, "{:?}", curve);
+        }
+    }
+
+    #[test]
+    fn should_match_reference_values_of_easings() {
+        // Assert
+        assert_eq!(Easing::In(EasingCurve::Quad).apply(0.5), 0.25);
+        assert_eq!(Easing::Out(EasingCurve::Cubic).apply(0.5), 0.875);
+        assert!((Easing::Out(EasingCurve::Bounce).apply(0.5) - 0.765625).abs() < 1e-6);
+        assert!(Easing::In(EasingCurve::Back).apply(0.3) < 0.0);
+        assert!(Easing::Out(EasingCurve::Elastic).apply(0.2) > 1.0);
+    }
+
+    #[test]
+    fn should_call_completion_callback_once_when_tween_finishes() {
+        // Arrange
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut tween = Tween::new(Vector3::new(0.0, 50.0, 0.0), Vector3::new(0.0, 10.0, 0.0), 2.0, Easing::InOut(EasingCurve::Quad))
+            .on_complete(move || counter.set(counter.get() + 1));
+
+        // Act
+        let halfway = tween.update(1.0);
+        let end = tween.update(1.5);
+        tween.update(1.0);
+
+        // Assert
+        assert!(halfway.approx_eq(&Vector3::new(0.0, 30.0, 0.0)));
+        assert!(end.approx_eq(&Vector3::new(0.0, 10.0, 0.0)));
+        assert!(tween.is_finished());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn should_continue_from_current_value_when_retargeting() {
+        // Arrange
+        let mut zoom = Tween::new(10.0, 20.0, 1.0, Easing::Linear);
+        zoom.update(0.5);
+
+        // Act
+        zoom.retarget(40.0);
+        let start = zoom.value();
+        let halfway = zoom.update(0.5);
+
+        // Assert
+        assert_eq!(start, 15.0);
+        assert_eq!(halfway, 27.5);
+    }
+
+    #[test]
+    fn should_slerp_when_tweening_rotation() {
+        // Arrange
+        let end = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 1.0);
+        let mut tween = Tween::new(Quaternion::identity(), end, 1.0, Easing::Linear);
+
+        // Act
+        let halfway = tween.update(0.5);
+
+        // Assert
+        assert!(halfway.approx_eq(&Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.5)));
+    }
+}
@@ -33,6 +33,7 @@ use winapi::um::d3dcommon::*;
 
 // Own modules
 pub mod obj_loader;
+pub mod scene_graph;
 
 // Std
 use std::collections::HashMap;
@@ -47,7 +48,7 @@ extern crate num_derive;
 
 #[repr(C)]
 struct VertexConstantBuffer {
-    worldViewProjection: beagle_math::Mat4 
+    worldViewProjection: beagle_math::GpuMat4
 }
 
 // TODO: Hot damn... really need to read up on all these fancy traits!
@@ -436,7 +437,9 @@ fn main() {
             ArraySize: 1,
             // Gotta read up on this format stuff with UNORM!
             // https://docs.microsoft.com/en-us/windows/win32/direct3d10/d3d10-graphics-programming-guide-resources-data-conversion
-            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            // The PNG stores sRGB encoded colors. With the _SRGB format, the GPU converts the texels to linear light when sampling,
+            // and back to sRGB when writing to the _SRGB back buffer. Without it, the colors were encoded twice and looked washed out.
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
             SampleDesc: DXGI_SAMPLE_DESC {
                 Count: 1,
                 Quality: 0
@@ -460,7 +463,7 @@ fn main() {
         }
 
         let mut shader_texture_view = D3D11_SHADER_RESOURCE_VIEW_DESC {
-            Format: DXGI_FORMAT_R8G8B8A8_UNORM,
+            Format: DXGI_FORMAT_R8G8B8A8_UNORM_SRGB,
             ViewDimension: D3D11_SRV_DIMENSION_TEXTURE2D,
             u: D3D11_SHADER_RESOURCE_VIEW_DESC_u::default()
         };
@@ -478,7 +481,13 @@ fn main() {
         // let loaded_model = obj_loader::load_obj();
         let path_to_cone_model = current_executable_path.parent().unwrap().join("resources\\media\\3d_models\\crate\\crate.obj");
 
-        let loaded_model_data = obj_loader::load_obj(path_to_cone_model);
+        let loaded_model_data = match obj_loader::load_obj(path_to_cone_model) {
+            Ok(model) => model,
+            Err(error) => {
+                println!("Failed to load model: {}", error);
+                return
+            }
+        };
 
         // https://docs.microsoft.com/en-us/windows/win32/api/d3d11/ns-d3d11-d3d11_buffer_desc 
         // D3D11_BUFFER_DESC is used to describe the buffer we want to upload
@@ -717,13 +726,14 @@ fn main() {
         let mut should_quit = false;
         let mut current_message = MSG::default();
 
-        let mut cam_x = 0.0;
-        let mut cam_y = 0.0;
-        let mut cam_z = 0.0;
+        let mut camera = Transform::identity();
 
-        let mut cam_rot_x = 0f32.to_radians();
-        let mut cam_rot_y = 0.0f32.to_radians();
-        let mut cam_rot_z = 0f32.to_radians();
+        // Everything drawn is placed in the scene graph, so attached parts follow the object they belong to.
+        let mut scene = scene_graph::SceneGraph::new();
+        let crate_node = scene.add_node("crate", Transform::identity(), None).expect("The scene should start out empty.");
+
+        // Rotation speed of the camera, in radians per frame.
+        let camera_rotation_step = 0.02;
 
         while !should_quit {
             // PeekMessage will retrieve messages associated with the main window.
@@ -738,45 +748,52 @@ fn main() {
             } else {
                 // UPDATE
                 if window_helper.is_key_pressed(KeyType::W) {
-                    cam_z += 0.2;
+                    camera.translation += Vector3::new(0.0, 0.0, 0.2);
                 }
 
                 if window_helper.is_key_pressed(KeyType::S) {
-                    cam_z -= 0.2;
+                    camera.translation -= Vector3::new(0.0, 0.0, 0.2);
                 }
 
                 if window_helper.is_key_pressed(KeyType::A) {
-                    cam_x -= 0.2;
+                    camera.translation -= Vector3::new(0.2, 0.0, 0.0);
                 }
 
                 if window_helper.is_key_pressed(KeyType::D) {
-                    cam_x += 0.2;
+                    camera.translation += Vector3::new(0.2, 0.0, 0.0);
                 }
 
                 if window_helper.is_key_pressed(KeyType::Q) {
-                    cam_y += 0.2;
+                    camera.translation += Vector3::new(0.0, 0.2, 0.0);
                 }
 
                 if window_helper.is_key_pressed(KeyType::E) {
-                    cam_y -= 0.2;
+                    camera.translation -= Vector3::new(0.0, 0.2, 0.0);
                 }
 
+                // Pitch happens around the camera's own X axis, so it is applied before the current orientation.
+                // Yaw happens around the world Y axis, so it is applied after the current orientation.
+                // The view matrix is the inverse of this rotation, so the angles are negated to turn the view the same way
+                // as the original controls, where Left looks left and Up looks up.
                 if window_helper.is_key_pressed(KeyType::Up) {
-                    cam_rot_x += 0.02;
+                    camera.rotation = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), -camera_rotation_step).mul(&camera.rotation);
                 }
 
                 if window_helper.is_key_pressed(KeyType::Down) {
-                    cam_rot_x -= 0.02;
+                    camera.rotation = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), camera_rotation_step).mul(&camera.rotation);
                 }
 
                 if window_helper.is_key_pressed(KeyType::Left) {
-                    cam_rot_y += 0.02;
+                    camera.rotation = camera.rotation.mul(&Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), -camera_rotation_step));
                 }
 
                 if window_helper.is_key_pressed(KeyType::Right) {
-                    cam_rot_y -= 0.02;
+                    camera.rotation = camera.rotation.mul(&Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), camera_rotation_step));
                 }
 
+                // Repeatedly composing rotations slowly accumulates floating point error, so keep the orientation at unit length.
+                camera.rotation = camera.rotation.normalize();
+
                 if window_helper.was_mouse_pressed(MouseKey::Left) {
                     println!("Mouse was pressed!");
                 }
@@ -799,31 +816,18 @@ fn main() {
 
                 let lol : *mut VertexConstantBuffer = mapped_resource.pData as *mut VertexConstantBuffer;
 
-                let mut eye_position = beagle_math::Vector3 {
-                    x: -cam_x,
-                    y: -cam_y,
-                    z: -cam_z 
-                };
-
-                let mut camera_postion = beagle_math::Mat4::translate(&eye_position);
-
-                let rotx = beagle_math::Mat4::rotate_x(cam_rot_x);
-                let roty = beagle_math::Mat4::rotate_y(cam_rot_y);
-                let rotz = beagle_math::Mat4::rotate_z(cam_rot_z);
-
-                // let mut totalt = roty.mul(&rotx);
-                let totalt = beagle_math::Quaternion::Rotation(Vector3::new(0.0, 0.0, 1.0), cam_rot_z)
-                .mul( &beagle_math::Quaternion::Rotation(Vector3::new(0.0, 1.0, 0.0), cam_rot_y))
-                .mul(&beagle_math::Quaternion::Rotation(Vector3::new(1.0, 0.0, 0.0), cam_rot_x));
+                // The camera is placed in the world like any other object, by first rotating and then translating it.
+                // The view matrix is the inverse of that world matrix, as it moves the world into the space of the camera.
+                let view = camera.to_mat4().inverse_affine().expect("The camera world matrix should always be invertible.");
 
                 // MY MATH LIBRARY CURRENTLY USES ROW-MAJOR CONVENTION, THIS MEANS THAT YOUR TYPICAL P * V * TRSv order becomes vSRT * VIEW * PROJECTION
-                (*lol).worldViewProjection = camera_postion.mul(&totalt).mul(&beagle_math::Mat4::projection((45.0f32).to_radians(), 800.0, 600.0, 0.1, 100.0));
+                let world_view_projection = scene.world_matrix(crate_node)
+                    .mul(&view)
+                    .mul(&beagle_math::Mat4::projection((45.0f32).to_radians(), 800.0, 600.0, 0.1, 100.0));
 
-                // My matrices are all designed for being multipled with a ROW vector.
-                // Also, I store my matrices in row-major order in memory.
-                // By default, HLSL will both READ and PACK matrices in column-major. 
-                // So I transpose my matrix so that it will be read correctly as a ROW MAJOR matrix on the shader side.
-                (*lol).worldViewProjection.tranpose();
+                // HLSL reads matrices in column-major order, so the matrix has to be converted before it is written to the constant buffer.
+                // The constant buffer only accepts a GpuMat4, so this conversion cannot be forgotten.
+                (*lol).worldViewProjection = world_view_projection.to_gpu();
 
                 // After we're done mapping new data, we have to call Unmap in order to invalidate the pointer to the buffer
                 // and reenable the GPU's access to that resource
@@ -838,7 +842,8 @@ fn main() {
                 immediate_device_context.as_ref().unwrap().VSSetShader(vertex_shader_instance, null_mut(), 0);
                 immediate_device_context.as_ref().unwrap().PSSetShader(pixel_shader_instance, null_mut(), 0);
 
-                let clear_color = beagle_math::Vector4::new(0.45, 0.6, 0.95, 1.0);
+                // The back buffer expects linear colors, so the sRGB sky blue is converted first.
+                let clear_color = beagle_math::Color::from_srgb(0.45, 0.6, 0.95, 1.0);
                 immediate_device_context.as_ref().unwrap().ClearRenderTargetView(back_buffer_view, &clear_color.as_array());
                 immediate_device_context.as_ref().unwrap().ClearDepthStencilView(depth_buffer_view, D3D11_CLEAR_DEPTH | D3D11_CLEAR_STENCIL, 1.0, 0);
 
@@ -865,7 +870,8 @@ fn create_swap_chain_description(main_window: *mut HWND__) -> DXGI_SWAP_CHAIN_DE
     // DXGI_MODE_DESC format of the buffer
     // DXGI_FORMAT_R8G8B8A8_UNORM = four component, 32-bit unsigned-normalized-integer which
     // supports 8 bits per channel, including alpha
-    swap_chain_description.BufferDesc.Format = DXGI_FORMAT_R8G8B8A8_UNORM;
+    // The _SRGB variant makes the GPU encode the linear colors written by the pixel shader to sRGB, which is what the monitor expects.
+    swap_chain_description.BufferDesc.Format = DXGI_FORMAT_R8G8B8A8_UNORM_SRGB;
 
     // DXGI_MODE_DESC scanline ordering is used to specify the method the raster uses to draw an
     // image.
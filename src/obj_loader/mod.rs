@@ -1,8 +1,8 @@
 // Modules are ways of organizing code within a crate for easy reuse and readability.
 // Modules also control the privacy of items. They will be private by default.
 // Modules are defined using the "mod" keyword.
-use std::{collections::HashMap, path::PathBuf};
-use std::io::*;
+use std::{collections::HashMap, fmt, path::{Path, PathBuf}};
+use std::io::{BufRead, BufReader, Error};
 use std::fs::File;
 
 // io::prelude is a module that alleviates imports of many common I/O traits
@@ -14,7 +14,7 @@ use crate::beagle_math::{self};
 pub struct Vertex {
     pub position: beagle_math::Vector3,
     pub uv: beagle_math::Vector2,
-    pub color: beagle_math::Vector4
+    pub color: beagle_math::Color
 }
 
 #[derive(Default)]
@@ -23,91 +23,448 @@ pub struct ObjLoaderResult {
     pub indices: Vec<i32>
 }
 
-pub fn load_obj(file_path: PathBuf) -> ObjLoaderResult {
-    // .expect is a method on the Result enum which will return the OK value in case of success
-    // And otherwise automaticlaly panic! in case of failure.a
+// Why a model could not be loaded.
+#[derive(Debug)]
+pub enum ObjErrorReason {
+    // The file could not be opened or read.
+    Io(Error),
+    // A number could not be parsed.
+    Parse,
+    // A line ended before all the values it needs, such as a vertex with only two coordinates.
+    MissingValue,
+    // A face refers to a vertex, texture coordinate or normal that has not been defined (yet).
+    IndexOutOfRange { index: i64, count: usize },
+    // A directive the loader does not understand, such as curves ("curv") or lines ("l").
+    UnsupportedDirective
+}
+
+// An error while loading a model, with enough information to find the problem in the file.
+// The line and column are counted from 1, and are None for errors that are not about a specific place, like a missing file.
+// The token is the part of the line the error is about, such as the number that could not be parsed.
+#[derive(Debug)]
+pub struct ObjError {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub token: Option<String>,
+    pub reason: ObjErrorReason
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+
+        match &self.reason {
+            ObjErrorReason::Io(error) => write!(f, ": failed to read the file: {}", error)?,
+            ObjErrorReason::Parse => write!(f, ": not a valid number")?,
+            ObjErrorReason::MissingValue => write!(f, ": missing value")?,
+            ObjErrorReason::IndexOutOfRange { index, count } => write!(f, ": index {} is out of range, as there are {} elements", index, count)?,
+            ObjErrorReason::UnsupportedDirective => write!(f, ": unsupported directive")?
+        }
+
+        if let Some(token) = &self.token {
+            write!(f, " (\"{}\")", token)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.reason {
+            ObjErrorReason::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+pub fn load_obj(file_path: PathBuf) -> Result<ObjLoaderResult, ObjError> {
     // First I get a handle to a file on the filesystem
-    let mut obj_file = File::open(file_path).expect("failed to read obj file");
+    let obj_file = match File::open(&file_path) {
+        Ok(file) => file,
+        Err(error) => return Err(ObjError {
+            path: file_path,
+            line: None,
+            column: None,
+            token: None,
+            reason: ObjErrorReason::Io(error)
+        })
+    };
 
     // Then I create a buffered reader to the file using the file handle.
-    let buff_read = BufReader::new(obj_file);
+    read_obj(BufReader::new(obj_file), &file_path)
+}
 
-    // Lastly, I get an iterator to all lines in the file.
-    let lines = buff_read.lines();
+// The position in the file currently being read, used to build the errors.
+struct Location<'a> {
+    path: &'a Path,
+    line: usize
+}
+
+impl<'a> Location<'a> {
+    fn error(&self, token: Option<&Token>, reason: ObjErrorReason) -> ObjError {
+        ObjError {
+            path: self.path.to_path_buf(),
+            line: Some(self.line),
+            column: token.map(|token| token.column),
+            token: token.map(|token| token.text.to_string()),
+            reason
+        }
+    }
+}
+
+// A whitespace separated part of a line, with the column it starts at.
+struct Token<'a> {
+    text: &'a str,
+    column: usize
+}
 
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (index, character) in line.char_indices().chain(std::iter::once((line.len(), ' '))) {
+        match (start, character.is_whitespace()) {
+            (None, false) => start = Some(index),
+            (Some(token_start), true) => {
+                tokens.push(Token {
+                    text: &line[token_start..index],
+                    column: line[..token_start].chars().count() + 1
+                });
+
+                start = None;
+            },
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+fn read_obj<R: BufRead>(reader: R, file_path: &Path) -> Result<ObjLoaderResult, ObjError> {
     let mut vertices : Vec<beagle_math::Vector3> = Vec::new();
     let mut uv : Vec<beagle_math::Vector2> = Vec::new();
+    let mut normal_count = 0;
     let mut indices : Vec<i32> = Vec::new();
 
     let mut vertex_objs : Vec<Vertex> = Vec::new();
 
-    let mut rofl : HashMap<String, usize> = HashMap::new();
+    // The index of the vertex already created for each combination of position, texture coordinate and normal.
+    let mut existing_vertices : HashMap<(usize, Option<usize>, Option<usize>), usize> = HashMap::new();
 
-    for current_line_result in lines {
-        let current_line = current_line_result.expect("Failed to fetch line from obj file.");
+    // Lastly, I get an iterator to all lines in the file.
+    for (line_index, current_line_result) in reader.lines().enumerate() {
+        let location = Location {
+            path: file_path,
+            line: line_index + 1
+        };
 
-        // TODO: Gotta read up on that difference between String and &str...
-        let parts : Vec<&str> = current_line.split_whitespace().collect();
+        let current_line = current_line_result.map_err(|error| location.error(None, ObjErrorReason::Io(error)))?;
+        let parts = tokenize(&current_line);
 
-        // Starting with "v", we got a vertex line
-        if parts[0] == "v" {
-            vertices.push( beagle_math::Vector3::new(
-                    parts[1].parse::<f32>().expect("Failed to convert vertice string to number"),
-                    parts[2].parse::<f32>().expect("Failed to convert vertice string to number"),
-                    parts[3].parse::<f32>().expect("Failed to convert vertice string to number")  + 1.0));
-         };
-        
+        // Blank lines are allowed anywhere.
+        let directive = match parts.first() {
+            Some(directive) => directive,
+            None => continue
+        };
 
-        // Starting with "vt" we got a texture coordinate
-        if parts[0] == "vt" {
-            uv.push(beagle_math::Vector2::new(
-                    parts[1].parse::<f32>().expect("Failed to convert UV u coordinate."),
-                    parts[2].parse::<f32>().expect("Failed to convert UV v coordinate.")));
-        }
+        match directive.text {
+            // Starting with "v", we got a vertex line
+            "v" => {
+                vertices.push(beagle_math::Vector3::new(
+                    parse_number(&parts, 1, &location)?,
+                    parse_number(&parts, 2, &location)?,
+                    parse_number(&parts, 3, &location)? + 1.0));
+            },
 
-        // Starting with "f", we get a face element
-        // A face can contain three OR MORE vertices
-        if parts[0] == "f" {
-            for n in 1..parts.len() {
-                let the_part = parse_face_element(parts[n]);
-
-                // Does vertex for this combination already exist
-                let haha = format!("{},{},{}", the_part[0], the_part[1], the_part[2]);
-                // println!("{}", haha);
-                match rofl.get(&haha) {
-                    Some(val) => {
-                        let the_value = *val;
-                        indices.push(the_value as i32)
-                    },
-                    None => {
-                        vertex_objs.push( Vertex {
-                            position: vertices[(the_part[0] - 1) as usize],
-                            uv: uv[(the_part[1] - 1) as usize],
-                            color: beagle_math::Vector4::default()
-                         });
-
-                        let new_index = vertex_objs.len() - 1;
-
-                        rofl.entry(haha).or_insert(new_index);
-
-                        indices.push(new_index as i32);
-                    }
+            // Starting with "vt" we got a texture coordinate
+            "vt" => {
+                uv.push(beagle_math::Vector2::new(
+                    parse_number(&parts, 1, &location)?,
+                    parse_number(&parts, 2, &location)?));
+            },
+
+            // Normals are not used by the Vertex yet, but are counted so the faces referring to them can be checked.
+            "vn" => {
+                for n in 1..=3 {
+                    parse_number(&parts, n, &location)?;
                 }
-            }
+
+                normal_count += 1;
+            },
+
+            // Starting with "f", we get a face element
+            // A face can contain three OR MORE vertices, which are split into triangles further down
+            "f" => {
+                if parts.len() < 4 {
+                    return Err(location.error(parts.last(), ObjErrorReason::MissingValue));
+                }
+
+                let mut face : Vec<usize> = Vec::with_capacity(parts.len() - 1);
+
+                for part in &parts[1..] {
+                    let key = parse_face_element(part, vertices.len(), uv.len(), normal_count, &location)?;
+
+                    // Does vertex for this combination already exist
+                    let index = match existing_vertices.get(&key) {
+                        Some(index) => *index,
+                        None => {
+                            vertex_objs.push(Vertex {
+                                position: vertices[key.0],
+                                uv: key.1.map_or(beagle_math::Vector2::default(), |uv_index| uv[uv_index]),
+                                color: beagle_math::Color::default()
+                            });
+
+                            existing_vertices.insert(key, vertex_objs.len() - 1);
+                            vertex_objs.len() - 1
+                        }
+                    };
+
+                    face.push(index);
+                }
+
+                // The index buffer is drawn as a triangle list, so faces with more than three vertices are split into triangles.
+                for triangle in triangulate_face(&face, &vertex_objs) {
+                    indices.extend(triangle.iter().map(|index| *index as i32));
+                }
+            },
+
+            // Comments, object and group names, smoothing groups and materials don't change the geometry, and are skipped.
+            text if text.starts_with('#') => {},
+            "o" | "g" | "s" | "mtllib" | "usemtl" => {},
+
+            _ => return Err(location.error(Some(directive), ObjErrorReason::UnsupportedDirective))
         }
     }
 
-    println!("THE COUNT {}", vertex_objs.len());
-
-    ObjLoaderResult {
+    Ok(ObjLoaderResult {
         vertices: vertex_objs,
         indices
+    })
+}
+
+fn parse_number(parts: &[Token], index: usize, location: &Location) -> Result<f32, ObjError> {
+    let token = match parts.get(index) {
+        Some(token) => token,
+        None => return Err(location.error(parts.last(), ObjErrorReason::MissingValue))
+    };
+
+    token.text.parse::<f32>().map_err(|_| location.error(Some(token), ObjErrorReason::Parse))
+}
+
+// Parses "v", "v/vt", "v//vn" or "v/vt/vn" into zero based indices.
+fn parse_face_element(
+    face_element: &Token,
+    position_count: usize,
+    uv_count: usize,
+    normal_count: usize,
+    location: &Location
+) -> Result<(usize, Option<usize>, Option<usize>), ObjError> {
+    let elements : Vec<&str> = face_element.text.split('/').collect();
+
+    if elements.len() > 3 {
+        return Err(location.error(Some(face_element), ObjErrorReason::Parse));
+    }
+
+    let index = |position: usize, count: usize| -> Result<Option<usize>, ObjError> {
+        match elements.get(position) {
+            None | Some(&"") => Ok(None),
+            Some(text) => {
+                let index = text.parse::<i64>().map_err(|_| location.error(Some(face_element), ObjErrorReason::Parse))?;
+                resolve_index(index, count).map(Some).ok_or_else(|| location.error(Some(face_element), ObjErrorReason::IndexOutOfRange { index, count }))
+            }
+        }
+    };
+
+    let position = index(0, position_count)?.ok_or_else(|| location.error(Some(face_element), ObjErrorReason::MissingValue))?;
+
+    Ok((position, index(1, uv_count)?, index(2, normal_count)?))
+}
+
+// Triangulates a face in the plane it lies in, keeping the winding order of the face.
+// The plane is found with Newell's method, which gives a sensible normal even for concave and slightly non-planar faces.
+// The face is then flattened by dropping the axis the normal points the most along, which can never flatten it into a line.
+fn triangulate_face(face: &[usize], vertices: &[Vertex]) -> Vec<[usize; 3]> {
+    // The face branch already rejects faces with fewer than three vertices, but those simply give no triangles here.
+    match face.len() {
+        0..=2 => return Vec::new(),
+        3 => return vec![[face[0], face[1], face[2]]],
+        _ => {}
+    }
+
+    let positions : Vec<beagle_math::Vector3> = face.iter().map(|index| vertices[*index].position).collect();
+    let mut normal = beagle_math::Vector3::default();
+
+    for (i, current) in positions.iter().enumerate() {
+        let next = positions[(i + 1) % positions.len()];
+
+        normal.x += (current.y - next.y) * (current.z + next.z);
+        normal.y += (current.z - next.z) * (current.x + next.x);
+        normal.z += (current.x - next.x) * (current.y + next.y);
+    }
+
+    let (x, y, z) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+
+    let flattened = positions.iter().map(|position| {
+        if x >= y && x >= z {
+            beagle_math::Vector2::new(position.y, position.z)
+        } else if y >= z {
+            beagle_math::Vector2::new(position.z, position.x)
+        } else {
+            beagle_math::Vector2::new(position.x, position.y)
+        }
+    }).collect();
+
+    match beagle_math::Polygon2::new(flattened) {
+        Some(polygon) => polygon.triangulate().iter().map(|[a, b, c]| [face[*a], face[*b], face[*c]]).collect(),
+        None => Vec::new()
+    }
+}
+
+// OBJ indices start at 1, and negative indices count backwards from the last element defined so far.
+fn resolve_index(index: i64, count: usize) -> Option<usize> {
+    let resolved = if index < 0 { count as i64 + index } else { index - 1 };
+
+    if index != 0 && (0..count as i64).contains(&resolved) {
+        Some(resolved as usize)
+    } else {
+        None
     }
 }
 
-fn parse_face_element(face_element: &str) -> Vec<i32> {
-    face_element
-        .split('/')
-        .map(|element| element.parse::<i32>().expect("Failed to parse element of face from obj line."))
-        .collect()
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::Path;
+
+    use crate::obj_loader::*;
+
+    fn read(text: &str) -> Result<ObjLoaderResult, ObjError> {
+        read_obj(Cursor::new(text), Path::new("models/test.obj"))
+    }
+
+    #[test]
+    fn should_skip_blank_lines_and_ignored_directives() {
+        // Arrange
+        let text = "# A triangle\n\nmtllib test.mtl\no Triangle\nv 0 0 0\nv 1 0 0\n   \nv 0 1 0\nvt 0 0\nvn 0 0 1\ns off\nf 1/1/1 2/1/1 -1/1/1\n";
+
+        // Act
+        let result = read(text).unwrap();
+
+        // Assert
+        assert_eq!(result.vertices.len(), 3);
+        assert_eq!(result.indices, vec![0, 1, 2]);
+    }
+
+    // The normal of every triangle in the index list, scaled by twice its area.
+    fn triangle_normals(result: &ObjLoaderResult) -> Vec<beagle_math::Vector3> {
+        result.indices.chunks(3).map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| result.vertices[triangle[i] as usize].position);
+            (b - a).cross(&(c - a))
+        }).collect()
+    }
+
+    #[test]
+    fn should_split_every_quad_into_two_triangles() {
+        // Arrange
+        // A cube made of six quads, all counter-clockwise when seen from outside.
+        let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\n\
+            f 1 4 3 2\nf 5 6 7 8\nf 1 2 6 5\nf 2 3 7 6\nf 3 4 8 7\nf 4 1 5 8\n";
+
+        // Act
+        let result = read(text).unwrap();
+
+        // Assert
+        assert_eq!(result.vertices.len(), 8);
+        assert_eq!(result.indices.len(), 36);
+
+        // Both triangles of a quad have the normal of the quad, pointing out of the cube.
+        let center = beagle_math::Vector3::new(0.5, 0.5, 1.5);
+        for (i, normal) in triangle_normals(&result).iter().enumerate() {
+            let corner = result.vertices[result.indices[i * 3] as usize].position;
+            assert!(normal.dot(&(corner - center)) > 0.0);
+            assert!((normal.length() - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn should_keep_winding_and_area_when_triangulating_mixed_faces() {
+        // Arrange
+        // A triangle, a quad and a concave pentagon (an arrow head), all counter-clockwise in the XY plane.
+        let text = "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+            v 2 0 0\nv 4 0 0\nv 4 2 0\nv 2 2 0\n\
+            v 5 0 0\nv 9 0 0\nv 9 4 0\nv 7 1 0\nv 5 4 0\n\
+            f 1 2 3\nf 4 5 6 7\nf 8 9 10 11 12\n";
+
+        // Act
+        let result = read(text).unwrap();
+        let normals = triangle_normals(&result);
+
+        // Assert
+        assert_eq!(result.indices.len(), 3 * (1 + 2 + 3));
+        assert!(normals.iter().all(|normal| normal.z > 0.0 && normal.x == 0.0 && normal.y == 0.0));
+
+        // Twice the areas: 1 for the triangle, 8 for the quad and 2 * (16 - 6) for the arrow head with its notch cut out.
+        let doubled_area: f32 = normals.iter().map(|normal| normal.z).sum();
+        assert_eq!(doubled_area, 1.0 + 8.0 + 20.0);
+    }
+
+    #[test]
+    fn should_report_line_column_and_token_when_number_is_invalid() {
+        // Act
+        let error = read("v 0 0 0\nv 1 0,5 0\n").err().unwrap();
+
+        // Assert
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.column, Some(5));
+        assert_eq!(error.token.as_deref(), Some("0,5"));
+        assert!(matches!(error.reason, ObjErrorReason::Parse));
+        assert_eq!(error.to_string(), "models/test.obj:2:5: not a valid number (\"0,5\")");
+    }
+
+    #[test]
+    fn should_report_index_out_of_range_when_face_refers_to_missing_vertex() {
+        // Act
+        let error = read("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\n").err().unwrap();
+        let zero = read("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n").err().unwrap();
+
+        // Assert
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.token.as_deref(), Some("4"));
+        assert!(matches!(error.reason, ObjErrorReason::IndexOutOfRange { index: 4, count: 3 }));
+        assert!(matches!(zero.reason, ObjErrorReason::IndexOutOfRange { index: 0, count: 3 }));
+    }
+
+    #[test]
+    fn should_report_unsupported_directive_and_missing_values() {
+        // Act
+        let unsupported = read("v 0 0 0\nl 1 2\n").err().unwrap();
+        let missing = read("v 0 0\n").err().unwrap();
+
+        // Assert
+        assert!(matches!(unsupported.reason, ObjErrorReason::UnsupportedDirective));
+        assert_eq!(unsupported.token.as_deref(), Some("l"));
+        assert!(matches!(missing.reason, ObjErrorReason::MissingValue));
+        assert_eq!(missing.line, Some(1));
+    }
+
+    #[test]
+    fn should_report_io_error_when_file_is_missing() {
+        // Act
+        let error = load_obj(PathBuf::from("does/not/exist.obj")).err().unwrap();
+
+        // Assert
+        assert!(matches!(error.reason, ObjErrorReason::Io(_)));
+        assert_eq!(error.line, None);
+        assert_eq!(error.path, PathBuf::from("does/not/exist.obj"));
+    }
 }
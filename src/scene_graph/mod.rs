@@ -0,0 +1,329 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use crate::beagle_math::{Mat4, Transform, Vector3};
+
+// Identifies a node in a SceneGraph. Ids are never reused, so the id of a removed node stays invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+struct Node {
+    name: String,
+    local: Transform,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    // The cached world matrix, which is only valid when "dirty" is false.
+    // Cells let the cache be refreshed while reading, so the graph can be drawn through a shared reference.
+    world: Cell<Mat4>,
+    dirty: Cell<bool>
+}
+
+// A hierarchy of transforms, where every node is placed relative to its parent, so attached parts like the legs,
+// thrusters, camera boom and exhaust emitters of the lander move along with its body.
+// The world matrix of a node is its local matrix followed by the world matrix of its parent: local * parent_world.
+// World matrices are cached. Changing a local transform marks the node and everything below it as dirty,
+// and the world matrices are only recomputed for dirty nodes, the next time they are asked for.
+// Every node has a unique name it can be found by.
+#[derive(Default)]
+pub struct SceneGraph {
+    nodes: Vec<Option<Node>>,
+    names: HashMap<String, NodeId>
+}
+
+impl SceneGraph {
+    pub fn new() -> SceneGraph {
+        SceneGraph::default()
+    }
+
+    // Adds a node at the root of the graph, or below "parent".
+    // Returns None if the name is already in use, or the parent has been removed.
+    pub fn add_node(&mut self, name: &str, local: Transform, parent: Option<NodeId>) -> Option<NodeId> {
+        if self.names.contains_key(name) {
+            return None;
+        }
+
+        if let Some(parent) = parent {
+            if !self.contains(parent) {
+                return None;
+            }
+        }
+
+        let id = NodeId(self.nodes.len());
+
+        self.nodes.push(Some(Node {
+            name: name.to_string(),
+            local,
+            parent,
+            children: Vec::new(),
+            world: Cell::new(Mat4::identity()),
+            dirty: Cell::new(true)
+        }));
+
+        self.names.insert(name.to_string(), id);
+
+        if let Some(parent) = parent {
+            self.node_mut(parent).children.push(id);
+        }
+
+        Some(id)
+    }
+
+    // Removes the node together with all of its descendants.
+    pub fn remove_node(&mut self, id: NodeId) {
+        if let Some(parent) = self.node(id).parent {
+            self.node_mut(parent).children.retain(|child| *child != id);
+        }
+
+        let mut to_remove = vec![id];
+
+        while let Some(current) = to_remove.pop() {
+            let node = self.nodes[current.0].take().unwrap();
+
+            self.names.remove(&node.name);
+            to_remove.extend(node.children);
+        }
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        matches!(self.nodes.get(id.0), Some(Some(_)))
+    }
+
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.names.get(name).copied()
+    }
+
+    // The methods below panic if the node has been removed, like indexing out of bounds.
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.node(id).name
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.node(id).children
+    }
+
+    // The nodes without a parent, in the order they were added.
+    pub fn roots(&self) -> Vec<NodeId> {
+        self.node_ids().filter(|id| self.node(*id).parent.is_none()).collect()
+    }
+
+    // Every node in the graph, in the order they were added.
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes.iter().enumerate().filter(|(_, node)| node.is_some()).map(|(index, _)| NodeId(index))
+    }
+
+    // Moves the node, with its descendants, below a new parent, or to the root with None.
+    // The local transform is kept, so the node moves in the world along with its new parent.
+    // Returns false, and changes nothing, if the new parent is the node itself or one of its descendants.
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> bool {
+        if let Some(parent) = parent {
+            if self.is_ancestor_or_self(id, parent) {
+                return false;
+            }
+        }
+
+        if let Some(old_parent) = self.node(id).parent {
+            self.node_mut(old_parent).children.retain(|child| *child != id);
+        }
+
+        if let Some(parent) = parent {
+            self.node_mut(parent).children.push(id);
+        }
+
+        self.node_mut(id).parent = parent;
+        self.mark_dirty(id);
+
+        true
+    }
+
+    pub fn local_transform(&self, id: NodeId) -> &Transform {
+        &self.node(id).local
+    }
+
+    pub fn set_local_transform(&mut self, id: NodeId, local: Transform) {
+        self.node_mut(id).local = local;
+        self.mark_dirty(id);
+    }
+
+    // The node is marked as dirty when the reference is handed out, since there is no way of knowing what is changed through it.
+    pub fn local_transform_mut(&mut self, id: NodeId) -> &mut Transform {
+        self.mark_dirty(id);
+        &mut self.node_mut(id).local
+    }
+
+    pub fn world_matrix(&self, id: NodeId) -> Mat4 {
+        let node = self.node(id);
+
+        if node.dirty.get() {
+            let local = node.local.to_mat4();
+
+            let world = match node.parent {
+                Some(parent) => local.mul(&self.world_matrix(parent)),
+                None => local
+            };
+
+            node.world.set(world);
+            node.dirty.set(false);
+        }
+
+        node.world.get()
+    }
+
+    pub fn world_position(&self, id: NodeId) -> Vector3 {
+        self.world_matrix(id).transform_point(&Vector3::new(0.0, 0.0, 0.0))
+    }
+
+    // The world transform of the node, as a TRS transform.
+    // Like Transform::compose, this is only an approximation below a non-uniformly scaled parent with a rotated child.
+    pub fn world_transform(&self, id: NodeId) -> Transform {
+        let node = self.node(id);
+
+        match node.parent {
+            Some(parent) => self.world_transform(parent).compose(&node.local),
+            None => node.local
+        }
+    }
+
+    fn is_ancestor_or_self(&self, ancestor: NodeId, id: NodeId) -> bool {
+        let mut current = Some(id);
+
+        while let Some(node) = current {
+            if node == ancestor {
+                return true;
+            }
+
+            current = self.node(node).parent;
+        }
+
+        false
+    }
+
+    // A dirty node always has dirty descendants, so a subtree that is already dirty does not have to be visited again.
+    fn mark_dirty(&self, id: NodeId) {
+        let mut to_mark = vec![id];
+
+        while let Some(current) = to_mark.pop() {
+            let node = self.node(current);
+
+            if !node.dirty.replace(true) {
+                to_mark.extend(node.children.iter().copied());
+            }
+        }
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes[id.0].as_ref().expect("The scene graph node has been removed.")
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.nodes[id.0].as_mut().expect("The scene graph node has been removed.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::beagle_math::*;
+    use crate::scene_graph::*;
+
+    // A lander at (0, 10, 0) with a leg one unit to the right and below it, and a foot at the end of the leg.
+    fn lander() -> (SceneGraph, NodeId, NodeId, NodeId) {
+        let mut graph = SceneGraph::new();
+
+        let body = graph.add_node("body", Transform::new(Vector3::new(0.0, 10.0, 0.0), Quaternion::identity(), Vector3::new(1.0, 1.0, 1.0)), None).unwrap();
+        let leg = graph.add_node("leg", Transform::new(Vector3::new(1.0, -1.0, 0.0), Quaternion::identity(), Vector3::new(1.0, 1.0, 1.0)), Some(body)).unwrap();
+        let foot = graph.add_node("foot", Transform::new(Vector3::new(0.0, -1.0, 0.0), Quaternion::identity(), Vector3::new(1.0, 1.0, 1.0)), Some(leg)).unwrap();
+
+        (graph, body, leg, foot)
+    }
+
+    #[test]
+    fn should_inherit_parent_transforms_when_computing_world_matrix() {
+        // Arrange
+        let (mut graph, body, _, foot) = lander();
+
+        // Act
+        graph.local_transform_mut(body).rotation = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_2);
+        let expected = graph.local_transform(foot).to_mat4()
+            .mul(&graph.local_transform(graph.parent(foot).unwrap()).to_mat4())
+            .mul(&graph.local_transform(body).to_mat4());
+
+        // Assert
+        assert!(graph.world_matrix(foot).approx_eq(&expected));
+        assert!(graph.world_position(foot).approx_eq(&expected.transform_point(&Vector3::new(0.0, 0.0, 0.0))));
+        assert!(graph.world_transform(foot).to_mat4().approx_eq(&expected));
+    }
+
+    #[test]
+    fn should_only_mark_subtree_dirty_when_changing_local_transform() {
+        // Arrange
+        let (mut graph, body, leg, foot) = lander();
+        let camera = graph.add_node("camera", Transform::identity(), Some(body)).unwrap();
+
+        for id in [body, leg, foot, camera].iter() {
+            graph.world_matrix(*id);
+        }
+
+        // Act
+        graph.local_transform_mut(leg).translation.x = 2.0;
+
+        // Assert
+        let dirty = |id: NodeId| graph.nodes[id.0].as_ref().unwrap().dirty.get();
+        assert!(!dirty(body));
+        assert!(!dirty(camera));
+        assert!(dirty(leg));
+        assert!(dirty(foot));
+        assert!(graph.world_position(foot).approx_eq(&Vector3::new(2.0, 8.0, 0.0)));
+        assert!(!dirty(foot));
+    }
+
+    #[test]
+    fn should_find_nodes_by_unique_name() {
+        // Arrange
+        let (mut graph, body, leg, _) = lander();
+
+        // Act
+        let duplicate = graph.add_node("leg", Transform::identity(), Some(body));
+
+        // Assert
+        assert_eq!(duplicate, None);
+        assert_eq!(graph.find("leg"), Some(leg));
+        assert_eq!(graph.name(leg), "leg");
+        assert_eq!(graph.find("thruster"), None);
+    }
+
+    #[test]
+    fn should_remove_descendants_when_removing_node() {
+        // Arrange
+        let (mut graph, body, leg, foot) = lander();
+
+        // Act
+        graph.remove_node(leg);
+
+        // Assert
+        assert!(!graph.contains(leg));
+        assert!(!graph.contains(foot));
+        assert_eq!(graph.find("foot"), None);
+        assert!(graph.children(body).is_empty());
+        assert_eq!(graph.node_ids().collect::<Vec<NodeId>>(), vec![body]);
+        assert!(graph.add_node("leg", Transform::identity(), Some(leg)).is_none());
+    }
+
+    #[test]
+    fn should_move_with_new_parent_and_reject_cycles_when_reparenting() {
+        // Arrange
+        let (mut graph, body, leg, foot) = lander();
+
+        // Act
+        let cycle = graph.set_parent(body, Some(foot));
+        let detached = graph.set_parent(leg, None);
+
+        // Assert
+        assert!(!cycle);
+        assert!(detached);
+        assert_eq!(graph.roots(), vec![body, leg]);
+        assert!(graph.world_position(foot).approx_eq(&Vector3::new(1.0, -2.0, 0.0)));
+    }
+}